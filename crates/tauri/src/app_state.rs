@@ -9,6 +9,7 @@ use serde::Serialize;
 use archive::{Registry, RegistryArchive};
 
 use crate::load_known_maps_info::{KnownMapInfo, KnownMaps};
+use crate::preview_cache::remove_cached_previews;
 
 #[derive(Debug, Clone, Serialize)]
 pub struct MapAndSaves {
@@ -109,6 +110,17 @@ impl AppState {
             .cloned()
     }
 
+    /// Returns the metadata of all installed and archived maps found by the last scans.
+    pub fn get_all_map_metadata(&self) -> Vec<MapMetadata> {
+        self.internal
+            .read()
+            .unwrap()
+            .maps_metadata
+            .values()
+            .cloned()
+            .collect()
+    }
+
     pub fn get_map_metadata_mut(&self, map_hash_id: &str) -> Option<MapMetadata> {
         self.internal
             .write()
@@ -157,12 +169,33 @@ impl AppState {
 
     // --- Public Setters ---
 
+    /// Registers the map metadata. A file already registered under another hash
+    /// changed its content, so the old hash is dropped together with its cached previews.
     pub fn set_map_metadata(&self, map_hash_id: &str, metadata: MapMetadata) {
-        self.internal
-            .write()
-            .unwrap()
-            .maps_metadata
-            .insert(map_hash_id.to_string(), metadata);
+        let stale_hash_ids: Vec<String> = {
+            let mut internal = self.internal.write().unwrap();
+            let stale_hash_ids: Vec<String> = internal
+                .maps_metadata
+                .iter()
+                .filter(|(hash_id, existing)| {
+                    hash_id.as_str() != map_hash_id && existing.file_path == metadata.file_path
+                })
+                .map(|(hash_id, _)| hash_id.clone())
+                .collect();
+
+            for hash_id in &stale_hash_ids {
+                internal.maps_metadata.remove(hash_id);
+            }
+            internal
+                .maps_metadata
+                .insert(map_hash_id.to_string(), metadata);
+
+            stale_hash_ids
+        };
+
+        for hash_id in stale_hash_ids {
+            remove_cached_previews(&hash_id);
+        }
     }
 
     //// pub fn set_selected_map_hash_id(&self, map_hash_id: Option<&str>) {
@@ -0,0 +1,26 @@
+use std::path::Path;
+
+use crate::GLOBAL_APP_STATE;
+
+/// Computes terrain statistics and landmass connectivity of a map.
+/// Sends the analysis to the front-end.
+#[tauri::command]
+pub async fn analyze_map_terrain_command(map_hash_id: String) -> Result<wrl::TerrainAnalysis, String> {
+    let app_state = GLOBAL_APP_STATE.clone();
+
+    let map_metadata = app_state.get_map_metadata(&map_hash_id).ok_or_else(|| {
+        log::error!("Map metadata not found for hash ID: {}", map_hash_id);
+        format!("Map with hash ID {} not found", map_hash_id)
+    })?;
+
+    let wrl_file_path = Path::new(&map_metadata.file_path);
+    let wrl_file = wrl::read_wrl_file(wrl_file_path).map_err(|e| {
+        log::error!("Failed to read WRL file: {}: {}", wrl_file_path.display(), e);
+        format!("Failed to read WRL file: {}: {}", wrl_file_path.display(), e)
+    })?;
+
+    wrl::analyze_terrain(&wrl_file).map_err(|e| {
+        log::error!("Failed to analyze terrain of map {}: {}", map_hash_id, e);
+        format!("Failed to analyze terrain: {}", e)
+    })
+}
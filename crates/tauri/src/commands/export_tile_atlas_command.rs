@@ -0,0 +1,30 @@
+use std::path::Path;
+
+use crate::GLOBAL_APP_STATE;
+
+/// Saves all tiles of a map as a PNG atlas with a JSON sidecar file.
+/// Sends the atlas metadata to the front-end.
+#[tauri::command]
+pub async fn export_tile_atlas_command(
+    map_hash_id: String,
+    output_file_path: String,
+) -> Result<wrl::TileAtlasMetadata, String> {
+    let app_state = GLOBAL_APP_STATE.clone();
+
+    let map_metadata = app_state.get_map_metadata(&map_hash_id).ok_or_else(|| {
+        log::error!("Map metadata not found for hash ID: {}", map_hash_id);
+        format!("Map with hash ID {} not found", map_hash_id)
+    })?;
+
+    let wrl_file_path = Path::new(&map_metadata.file_path);
+    let png_output_file = Path::new(&output_file_path);
+
+    wrl::save_tile_atlas_to_file(wrl_file_path, png_output_file, None).map_err(|e| {
+        log::error!(
+            "Failed to export tile atlas for map {}: {}",
+            map_hash_id,
+            e
+        );
+        format!("Failed to export tile atlas: {}", e)
+    })
+}
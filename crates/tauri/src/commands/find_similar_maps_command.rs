@@ -0,0 +1,26 @@
+use std::path::Path;
+
+use crate::GLOBAL_APP_STATE;
+
+/// Groups the installed and archived maps into clusters of probable variants of the same map.
+/// Maps which can not be read are left out. Sends the clusters to the front-end.
+#[tauri::command]
+pub async fn find_similar_maps_command(threshold: Option<f64>) -> Result<Vec<wrl::MapCluster>, String> {
+    let app_state = GLOBAL_APP_STATE.clone();
+    let threshold = threshold.unwrap_or(wrl::SIMILAR_MAP_THRESHOLD);
+
+    let mut fingerprints = Vec::new();
+    for map_metadata in app_state.get_all_map_metadata() {
+        let wrl_file_path = Path::new(&map_metadata.file_path);
+        match wrl::WrlView::open(wrl_file_path) {
+            Ok(wrl_view) => {
+                fingerprints.push((map_metadata.map_hash_id, wrl::fingerprint_wrl_view(&wrl_view)));
+            }
+            Err(e) => {
+                log::error!("Failed to read WRL file: {}: {}", wrl_file_path.display(), e);
+            }
+        }
+    }
+
+    Ok(wrl::cluster_similar_maps(&fingerprints, threshold))
+}
@@ -0,0 +1,11 @@
+use std::path::Path;
+
+/// Rewrites a stale tail header `hash_id` of a map file.
+/// Sends `true` to the front-end if the tail header was updated.
+#[tauri::command]
+pub async fn fix_tail_header_hash_command(file_path: String) -> Result<bool, String> {
+    wrl::fix_tail_header_hash(Path::new(&file_path)).map_err(|e| {
+        log::error!("Failed to fix tail header hash_id: {}: {}", file_path, e);
+        format!("Failed to fix tail header hash_id: {}: {}", file_path, e)
+    })
+}
@@ -2,7 +2,7 @@ use std::path::Path;
 
 use wrl;
 
-use crate::GLOBAL_APP_STATE;
+use crate::{GLOBAL_APP_STATE, preview_cache::get_tile_mipmaps};
 
 #[tauri::command]
 pub async fn generate_bigmap_preview_command(
@@ -18,15 +18,19 @@ pub async fn generate_bigmap_preview_command(
 
     let wrl_file_path = Path::new(&map_metadata.file_path);
     let wrl_file = wrl::read_wrl_file(&wrl_file_path).unwrap();
+    let mipmaps = get_tile_mipmaps(map_hash_id, || {
+        wrl::TileMipmaps::for_bigmap(&wrl_file.bigmap, &wrl_file.tiles, &wrl_file.palette)
+    });
     let preview = wrl::generate_bigmap_preview(
         &wrl_file.bigmap,
-        &wrl_file.tiles,
-        &wrl_file.palette,
+        &mipmaps,
+        &wrl_file.pass_table,
         wrl_file.width,
         wrl_file.height,
-        256,
-        256,
-        scanline,
+        wrl::PreviewOptions {
+            scanline,
+            ..wrl::PreviewOptions::default()
+        },
     );
     Ok(preview)
 }
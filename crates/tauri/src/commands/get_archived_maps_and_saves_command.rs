@@ -68,7 +68,17 @@ fn find_archived_wrl_files() -> Vec<PathBuf> {
             .join(&map_and_saves.0)
             .join(&map_and_saves.1.map);
         if map_path.exists() {
-            let map_hash_id = wrl::hash_wrl_file_without_tail(&map_path).unwrap();
+            let map_hash_id = match wrl::hash_wrl_file_without_tail(&map_path) {
+                Ok(map_hash_id) => map_hash_id,
+                Err(e) => {
+                    log::error!(
+                        "Skipping invalid archived WRL file: {}: {}",
+                        map_path.display(),
+                        e
+                    );
+                    continue;
+                }
+            };
             app_state.set_map_metadata(
                 &map_hash_id.clone(),
                 MapMetadata {
@@ -47,8 +47,18 @@ fn find_wrl_files(game_dir_path: &Path) -> Vec<PathBuf> {
             }
             if let Some(ext) = entry.path().extension() {
                 if ext.to_ascii_uppercase() == "WRL" {
+                    let map_hash_id = match wrl::hash_wrl_file_without_tail(&entry.path()) {
+                        Ok(map_hash_id) => map_hash_id,
+                        Err(e) => {
+                            log::error!(
+                                "Skipping invalid WRL file: {}: {}",
+                                entry.path().display(),
+                                e
+                            );
+                            continue;
+                        }
+                    };
                     wrl_files.push(entry.path());
-                    let map_hash_id = wrl::hash_wrl_file_without_tail(&entry.path()).unwrap();
                     app_state.set_map_metadata(
                         &map_hash_id.clone(),
                         MapMetadata {
@@ -4,8 +4,15 @@ use wrl;
 
 use crate::GLOBAL_APP_STATE;
 
+/// Copies the imported map into the installation slot.
+/// With `fix_tail_header` set, a stale tail header `hash_id` is accepted and fixed in the installed copy,
+/// the imported file itself is never modified.
 #[tauri::command]
-pub async fn install_imported_map_command(import_path: String, slot_name: String) -> Result<bool, String> {
+pub async fn install_imported_map_command(
+    import_path: String,
+    slot_name: String,
+    fix_tail_header: bool,
+) -> Result<bool, String> {
     let app_state = GLOBAL_APP_STATE.clone();
     let max_path = app_state.game_dir_path();
 	let file_path = std::path::Path::new(&import_path);
@@ -18,25 +25,38 @@ pub async fn install_imported_map_command(import_path: String, slot_name: String
 				return Err(error_message);
 			}
 		}
+		Err(wrl::WrlError::HashMismatch { .. }) if fix_tail_header => {}
 		Err(e) => {
-			let error_message = format!("Error verifying WRL file: {}", import_path);
+			let error_message = format!("Error verifying WRL file: {}: {}", import_path, e);
 			log::error!("{}", error_message);
-			log::error!("{}", e);
 			return Err(error_message);
 		}
 	}
 
-	let is_file_valid = wrl::verify_file(&file_path).unwrap_or(false);
-	if !is_file_valid {
-		let error_message = format!("Invalid WRL file: {}", import_path);
+	let lint_report = match wrl::lint_wrl_file(&file_path) {
+		Ok(report) => report,
+		Err(e) => {
+			let error_message = format!("Failed to lint WRL file: {}: {}", import_path, e);
+			log::error!("{}", error_message);
+			return Err(error_message);
+		}
+	};
+
+	for finding in &lint_report.findings {
+		log::warn!("{}: {}", import_path, finding);
+	}
+
+	if lint_report.has_errors() {
+		let findings: Vec<String> = lint_report.findings.iter().map(|finding| finding.to_string()).collect();
+		let error_message = format!("WRL file has lint errors: {}\n{}", import_path, findings.join("\n"));
 		log::error!("{}", error_message);
 		return Err(error_message);
 	}
 
 	let file_hash = match wrl::file::hash_wrl_file_without_tail(&file_path) {
 		Ok(hash) => hash,
-		Err(_) => {
-			let error_message = format!("Failed to calculate WRL file hash: {}", import_path);
+		Err(e) => {
+			let error_message = format!("Failed to calculate WRL file hash: {}: {}", import_path, e);
 			log::error!("{}", error_message);
 			return Err(error_message);
 		}
@@ -63,15 +83,21 @@ pub async fn install_imported_map_command(import_path: String, slot_name: String
 		return Err(error_message);
 	}
 
-	match std::fs::copy(&import_path, max_path.join(format!("{}.WRL", slot_name))) {
-		Ok(_) => {
-			Ok(true)
-		}
-		Err(e) => {
-			let error_message = format!("Failed to copy WRL file to game directory: {}", import_path);
-			log::error!("{}", error_message);
-			log::error!("{}", e);
-			Err(error_message)
-		}
+	let installed_path = max_path.join(format!("{}.WRL", slot_name));
+	if let Err(e) = std::fs::copy(&import_path, &installed_path) {
+		let error_message = format!("Failed to copy WRL file to game directory: {}", import_path);
+		log::error!("{}", error_message);
+		log::error!("{}", e);
+		return Err(error_message);
 	}
+
+	if fix_tail_header
+		&& let Err(e) = wrl::fix_tail_header_hash(&installed_path)
+	{
+		let error_message = format!("Failed to fix tail header of installed map: {}: {}", installed_path.display(), e);
+		log::error!("{}", error_message);
+		return Err(error_message);
+	}
+
+	Ok(true)
 }
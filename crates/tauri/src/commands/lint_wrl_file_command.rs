@@ -0,0 +1,11 @@
+use std::path::Path;
+
+/// Runs the WRL lint checks on a map file.
+/// Sends the list of findings to the front-end.
+#[tauri::command]
+pub async fn lint_wrl_file_command(file_path: String) -> Result<wrl::LintReport, String> {
+    wrl::lint_wrl_file(Path::new(&file_path)).map_err(|e| {
+        log::error!("Failed to lint WRL file: {}: {}", file_path, e);
+        format!("Failed to lint WRL file: {}: {}", file_path, e)
+    })
+}
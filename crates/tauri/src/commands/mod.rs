@@ -1,6 +1,18 @@
+pub mod analyze_map_terrain_command;
+pub use analyze_map_terrain_command::*;
+
 pub mod archive_map_and_saves_command;
 pub use archive_map_and_saves_command::*;
 
+pub mod export_tile_atlas_command;
+pub use export_tile_atlas_command::*;
+
+pub mod find_similar_maps_command;
+pub use find_similar_maps_command::*;
+
+pub mod fix_tail_header_hash_command;
+pub use fix_tail_header_hash_command::*;
+
 pub mod generate_bigmap_preview_command;
 pub use generate_bigmap_preview_command::*;
 
@@ -22,6 +34,9 @@ pub use get_installed_maps_and_saves_command::*;
 pub mod open_dir_path_in_file_explorer_command;
 pub use open_dir_path_in_file_explorer_command::*;
 
+pub mod lint_wrl_file_command;
+pub use lint_wrl_file_command::*;
+
 pub mod open_devtools_command;
 pub use open_devtools_command::*;
 
@@ -48,3 +63,6 @@ pub use verify_dir_path_command::*;
 
 pub mod verify_game_path_command;
 pub use verify_game_path_command::*;
+
+pub mod verify_tail_header_command;
+pub use verify_tail_header_command::*;
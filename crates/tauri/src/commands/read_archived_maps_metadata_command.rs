@@ -23,17 +23,33 @@ pub fn read_archived_maps_metadata_command() -> Result<Vec<wrl::MapMetadata>, St
     }
 
     for map_file_path in map_file_paths {
-        let map_header = wrl::read_wrl_header(&map_file_path).map_err(|e| {
-            format!(
-                "Failed to read WRL header for file {}: {}",
-                map_file_path.display(),
-                e
-            )
-        })?;
+        let map_header = match wrl::read_wrl_header(&map_file_path) {
+            Ok(header) => header,
+            Err(e) => {
+                log::error!(
+                    "Failed to read WRL header for file {}: {}",
+                    map_file_path.display(),
+                    e
+                );
+                continue;
+            }
+        };
+
+        // The content is hashed once, both to spot a stale tail header and to look up known maps.
+        let hash_id = match wrl::hash_wrl_file_without_tail(&map_file_path) {
+            Ok(hash_id) => hash_id,
+            Err(e) => {
+                log::error!(
+                    "Failed to hash WRL file {}: {}",
+                    map_file_path.display(),
+                    e
+                );
+                continue;
+            }
+        };
 
-        let mut map_tail = match wrl::read_wrl_tail_header(&map_file_path) {
-            Ok(Some(header)) => header,
-            Ok(None) => wrl::structs::WRLTailHeader::new("".to_string()),
+        let map_tail = match wrl::read_wrl_tail_header(&map_file_path) {
+            Ok(tail) => tail,
             Err(e) => {
                 log::error!(
                     "Failed to read WRL tail header for file {}: {}",
@@ -44,16 +60,27 @@ pub fn read_archived_maps_metadata_command() -> Result<Vec<wrl::MapMetadata>, St
             }
         };
 
-        if let Some(known_map) = app_state.get_known_map_info(&map_tail.hash_id) {
+        let tail_hash_mismatch = map_tail
+            .as_ref()
+            .is_some_and(|tail| tail.hash_id != hash_id);
+        let mut map_tail =
+            map_tail.unwrap_or_else(|| wrl::structs::WRLTailHeader::new(hash_id.clone()));
+
+        if let Some(known_map) = app_state.get_known_map_info(&hash_id) {
             map_tail.name = known_map.name;
             map_tail.description = known_map.description;
             map_tail.version = known_map.version;
-            map_tail.author = known_map.author;
+            map_tail.authors = if known_map.author.is_empty() {
+                Vec::new()
+            } else {
+                vec![known_map.author]
+            };
             map_tail.date = known_map.date;
         }
 
         let map_metadata = wrl::MapMetadata {
             file_path: map_file_path.to_string_lossy().to_string(),
+            format_version: map_header.format_version,
             width: map_header.width,
             height: map_header.height,
             minimap: wrl::preview::generate_minimap_preview(
@@ -63,6 +90,7 @@ pub fn read_archived_maps_metadata_command() -> Result<Vec<wrl::MapMetadata>, St
                 map_header.height,
             ),
             tail: map_tail,
+            tail_hash_mismatch,
         };
         maps_metadata.push(map_metadata);
     }
@@ -0,0 +1,29 @@
+use std::path::Path;
+
+use serde::Serialize;
+
+/// Result of the tail header verification sent to the front-end.
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "status", rename_all = "snake_case")]
+pub enum TailHeaderVerification {
+    /// The tail header matches the map content, or the map has no tail header.
+    Valid,
+    /// The tail header `hash_id` is stale, `fix_tail_header_hash_command` can rewrite it.
+    HashMismatch { expected: String, found: String },
+}
+
+/// Checks that the tail header `hash_id` of a map file matches its content.
+/// Sends the verification result to the front-end, unreadable files are an error.
+#[tauri::command]
+pub async fn verify_tail_header_command(file_path: String) -> Result<TailHeaderVerification, String> {
+    match wrl::verify_tail_header(Path::new(&file_path)) {
+        Ok(()) => Ok(TailHeaderVerification::Valid),
+        Err(wrl::WrlError::HashMismatch { expected, found }) => {
+            Ok(TailHeaderVerification::HashMismatch { expected, found })
+        }
+        Err(e) => {
+            log::error!("Failed to verify tail header: {}: {}", file_path, e);
+            Err(format!("Failed to verify tail header: {}: {}", file_path, e))
+        }
+    }
+}
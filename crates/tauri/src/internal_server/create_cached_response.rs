@@ -0,0 +1,45 @@
+use tauri::http;
+
+/// Previews are addressed by the map hash, so they never change for the same URL.
+const PREVIEW_CACHE_CONTROL: &str = "public, max-age=31536000, immutable";
+
+pub fn create_cached_response(
+    body: Vec<u8>,
+    content_type: &str,
+    etag: &str,
+) -> http::Response<Vec<u8>> {
+    http::Response::builder()
+        .header("Content-Type", content_type)
+        .header("ETag", etag)
+        .header("Cache-Control", PREVIEW_CACHE_CONTROL)
+        .header("Access-Control-Allow-Origin", "*")
+        .header("Access-Control-Allow-Methods", "GET, POST, OPTIONS")
+        .header(
+            "Access-Control-Allow-Headers",
+            "Content-Type, Authorization, If-None-Match",
+        )
+        .header("Access-Control-Expose-Headers", "ETag")
+        .body(body)
+        .expect("Failed to build response")
+}
+
+pub fn create_not_modified_response(etag: &str) -> http::Response<Vec<u8>> {
+    http::Response::builder()
+        .status(304)
+        .header("ETag", etag)
+        .header("Cache-Control", PREVIEW_CACHE_CONTROL)
+        .header("Access-Control-Allow-Origin", "*")
+        .header("Access-Control-Expose-Headers", "ETag")
+        .body(Vec::new())
+        .expect("Failed to build 304 response")
+}
+
+/// Checks the `If-None-Match` request header against the entity tag of the response.
+pub fn matches_etag(if_none_match: Option<&str>, etag: &str) -> bool {
+    if_none_match.is_some_and(|value| {
+        value
+            .split(',')
+            .map(|tag| tag.trim().trim_start_matches("W/"))
+            .any(|tag| tag == "*" || tag == etag)
+    })
+}
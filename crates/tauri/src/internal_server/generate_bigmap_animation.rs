@@ -0,0 +1,51 @@
+use std::path::Path;
+
+use crate::GLOBAL_APP_STATE;
+
+/// Renders one full palette cycle of a map preview as animated PNG.
+/// Non-square maps are letterboxed into the given size.
+pub fn generate_bigmap_animation(
+    map_hash_id: &str,
+    width: usize,
+    height: usize,
+) -> Result<Vec<u8>, String> {
+    let app_state = GLOBAL_APP_STATE.clone();
+    app_state.reload_archive_registry();
+
+    let map_metadata = app_state.get_map_metadata(map_hash_id).ok_or_else(|| {
+        log::error!("Map metadata not found for hash ID: {}", map_hash_id);
+        String::new()
+    })?;
+
+    let wrl_file_path = Path::new(&map_metadata.file_path);
+    let wrl_view = wrl::WrlView::open(wrl_file_path).map_err(|e| {
+        log::error!("Failed to read WRL file: {}: {}", wrl_file_path.display(), e);
+        format!("Failed to read WRL file: {}: {}", wrl_file_path.display(), e)
+    })?;
+
+    let frames = wrl::generate_bigmap_animation_frames(
+        &wrl_view,
+        wrl::PreviewOptions {
+            target_pixel_width: width,
+            target_pixel_height: height,
+            ..wrl::PreviewOptions::default()
+        },
+        &wrl::PLANET_PALETTE_CYCLE_RANGES,
+    )
+    .map_err(|e| {
+        log::error!("Failed to read WRL file: {}: {}", wrl_file_path.display(), e);
+        format!("Failed to read WRL file: {}: {}", wrl_file_path.display(), e)
+    })?;
+
+    let mut image_data = Vec::new();
+    wrl::write_animated_png(
+        &frames,
+        width as u32,
+        height as u32,
+        wrl::PALETTE_CYCLE_FRAME_DELAY_MS,
+        &mut image_data,
+    )
+    .map_err(|e| format!("Failed to encode animation: {}", e))?;
+
+    Ok(image_data)
+}
@@ -1,12 +1,12 @@
 use std::path::Path;
 
-use crate::GLOBAL_APP_STATE;
+use crate::{GLOBAL_APP_STATE, preview_cache::get_tile_mipmaps};
 
+/// Renders the bigmap preview, returning the RGBA pixels with their width and height.
 pub fn generate_bigmap_preview(
     map_hash_id: &str,
-    size: usize,
-    scanline: bool,
-) -> Result<Vec<u8>, String> {
+    options: wrl::PreviewOptions,
+) -> Result<(Vec<u8>, usize, usize), String> {
     let app_state = GLOBAL_APP_STATE.clone();
     app_state.reload_archive_registry();
 
@@ -16,19 +16,26 @@ pub fn generate_bigmap_preview(
     })?;
 
     let wrl_file_path = Path::new(&map_metadata.file_path);
-    let wrl_file = wrl::read_wrl_file(&wrl_file_path).map_err(|_| {
-        log::error!("Failed to read WRL file: {}", wrl_file_path.display());
-        format!("Failed to read WRL file: {}", wrl_file_path.display())
+    let wrl_view = wrl::WrlView::open(wrl_file_path).map_err(|e| {
+        log::error!("Failed to read WRL file: {}: {}", wrl_file_path.display(), e);
+        format!("Failed to read WRL file: {}: {}", wrl_file_path.display(), e)
     })?;
+    let pass_table = wrl_view.pass_table().map_err(|e| {
+        log::error!("Failed to read WRL file: {}: {}", wrl_file_path.display(), e);
+        format!("Failed to read WRL file: {}: {}", wrl_file_path.display(), e)
+    })?;
+    let bigmap = wrl_view.bigmap();
+    let mipmaps = get_tile_mipmaps(map_hash_id, || {
+        wrl::TileMipmaps::for_bigmap(&bigmap, wrl_view.tiles(), wrl_view.palette())
+    });
     let preview = wrl::generate_bigmap_preview(
-        &wrl_file.bigmap,
-        &wrl_file.tiles,
-        &wrl_file.palette,
-        wrl_file.width,
-        wrl_file.height,
-        size,
-        size,
-        scanline,
+        &bigmap,
+        &mipmaps,
+        pass_table,
+        wrl_view.width(),
+        wrl_view.height(),
+        options,
     );
-    Ok(preview)
+    let (output_width, output_height) = options.output_size(wrl_view.width(), wrl_view.height());
+    Ok((preview, output_width, output_height))
 }
@@ -5,15 +5,18 @@ use crate::{
     internal_server::{
         create_error_response::create_error_response,
         request_handlers::{
-            get_file_request, get_res_image_request, get_wrl_bigmap_request,
-            get_wrl_minimap_request,
+            get_file_request, get_res_image_request, get_wrl_bigmap_animation_request,
+            get_wrl_bigmap_request, get_wrl_minimap_request,
         },
     },
 };
 
+mod create_cached_response;
 mod create_error_response;
+mod generate_bigmap_animation;
 mod generate_bigmap_preview;
 mod lre;
+mod parse_preview_size;
 mod request_handlers;
 
 pub fn handle_request(request: http::Request<Vec<u8>>) -> http::Response<Vec<u8>> {
@@ -39,6 +42,10 @@ pub fn handle_request(request: http::Request<Vec<u8>>) -> http::Response<Vec<u8>
     }
 
     let asset_type = url_parts[1];
+    let if_none_match = request
+        .headers()
+        .get("If-None-Match")
+        .and_then(|value| value.to_str().ok());
 
     if asset_type == "get-file" {
         return get_file_request(url_parts, &path);
@@ -51,9 +58,11 @@ pub fn handle_request(request: http::Request<Vec<u8>>) -> http::Response<Vec<u8>
     if asset_type == "get-res-image" {
         return get_res_image_request(url_parts);
     } else if asset_type == "get-wrl-minimap" {
-        return get_wrl_minimap_request(url_parts);
+        return get_wrl_minimap_request(url_parts, if_none_match);
     } else if asset_type == "get-wrl-bigmap" {
-        return get_wrl_bigmap_request(url_parts, &path);
+        return get_wrl_bigmap_request(url_parts, &path, if_none_match);
+    } else if asset_type == "get-wrl-bigmap-animation" {
+        return get_wrl_bigmap_animation_request(url_parts, if_none_match);
     } else {
         return create_error_response(500, "Unknown asset type");
     }
@@ -0,0 +1,10 @@
+/// Parses a preview size URL part, either `<size>` for a square preview or `<width>x<height>`.
+pub fn parse_preview_size(value: &str) -> Option<(usize, usize)> {
+    match value.split_once('x') {
+        Some((width, height)) => Some((width.parse().ok()?, height.parse().ok()?)),
+        None => {
+            let size = value.parse().ok()?;
+            Some((size, size))
+        }
+    }
+}
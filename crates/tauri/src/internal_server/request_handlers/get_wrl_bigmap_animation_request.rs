@@ -0,0 +1,72 @@
+use tauri::http;
+
+use crate::{
+    internal_server::{
+        create_cached_response::{
+            create_cached_response, create_not_modified_response, matches_etag,
+        },
+        create_error_response::create_error_response,
+        generate_bigmap_animation::generate_bigmap_animation,
+        parse_preview_size::parse_preview_size,
+    },
+    preview_cache::{PreviewCacheKey, PreviewKind, get_cached_preview, store_cached_preview},
+};
+
+/// Handles `get-wrl-bigmap-animation/<map_hash_id>[/<size>|<width>x<height>]` requests.
+/// The animation always covers one full palette cycle, so it loops without a jump.
+/// Rendered animations are served from the on-disk preview cache when available.
+pub fn get_wrl_bigmap_animation_request(
+    url_parts: Vec<&str>,
+    if_none_match: Option<&str>,
+) -> http::Response<Vec<u8>> {
+    let map_hash_id = url_parts[2];
+    let mut width: usize = 256;
+    let mut height: usize = 256;
+
+    if url_parts.len() >= 4 {
+        match parse_preview_size(url_parts[3]) {
+            Some((w, h)) => {
+                width = w;
+                height = h;
+            }
+            None => {
+                return create_error_response(400, "Invalid size parameter");
+            }
+        }
+    }
+
+    let cache_key = PreviewCacheKey {
+        map_hash_id,
+        kind: PreviewKind::BigmapAnimation,
+        width,
+        height,
+        fit: wrl::PreviewFit::Letterbox,
+        scanline: false,
+        overlay: wrl::PreviewOverlay::None,
+    };
+    let etag = cache_key.etag();
+
+    if matches_etag(if_none_match, &etag) {
+        return create_not_modified_response(&etag);
+    }
+
+    if let Some(image_data) = get_cached_preview(&cache_key) {
+        return create_cached_response(image_data, "image/apng", &etag);
+    }
+
+    let image_data = match generate_bigmap_animation(map_hash_id, width, height) {
+        Ok(image_data) => image_data,
+        Err(e) => {
+            log::error!(
+                "Failed to generate bigmap animation for map {}: {}",
+                map_hash_id,
+                e
+            );
+            return create_error_response(500, "Failed to generate bigmap animation");
+        }
+    };
+
+    store_cached_preview(&cache_key, &image_data);
+
+    create_cached_response(image_data, "image/apng", &etag)
+}
@@ -1,48 +1,108 @@
 use tauri::http;
 
-use crate::internal_server::{
-    create_error_response::create_error_response, generate_bigmap_preview::generate_bigmap_preview,
+use crate::{
+    internal_server::{
+        create_cached_response::{
+            create_cached_response, create_not_modified_response, matches_etag,
+        },
+        create_error_response::create_error_response,
+        generate_bigmap_preview::generate_bigmap_preview,
+        parse_preview_size::parse_preview_size,
+    },
+    preview_cache::{PreviewCacheKey, PreviewKind, get_cached_preview, store_cached_preview},
 };
 
-pub fn get_wrl_bigmap_request(url_parts: Vec<&str>, _path: &str) -> http::Response<Vec<u8>> {
+/// Handles `get-wrl-bigmap/<map_hash_id>[/<size>|<width>x<height>[/<overlay>[/<fit>]]]` requests.
+/// Rendered previews are served from the on-disk preview cache when available.
+pub fn get_wrl_bigmap_request(
+    url_parts: Vec<&str>,
+    _path: &str,
+    if_none_match: Option<&str>,
+) -> http::Response<Vec<u8>> {
     let map_hash_id = url_parts[2];
-    let mut size: usize = 256;
+    let mut width: usize = 256;
+    let mut height: usize = 256;
+    let mut overlay = wrl::PreviewOverlay::None;
+    let mut fit = wrl::PreviewFit::Letterbox;
 
     if url_parts.len() >= 4 {
-        match url_parts[3].parse::<usize>() {
-            Ok(s) => {
-                size = s;
+        match parse_preview_size(url_parts[3]) {
+            Some((w, h)) => {
+                width = w;
+                height = h;
             }
-            _ => {
+            None => {
                 return create_error_response(400, "Invalid size parameter");
             }
         }
     }
 
-    let minimap_pixels = generate_bigmap_preview(map_hash_id, size, false).unwrap_or_else(|e| {
-        log::error!(
-            "Failed to generate bigmap preview for map {}: {}",
-            map_hash_id,
-            e
-        );
-        Vec::new()
-    });
+    if url_parts.len() >= 5 {
+        match wrl::PreviewOverlay::from_name(url_parts[4]) {
+            Some(o) => {
+                overlay = o;
+            }
+            None => {
+                return create_error_response(400, "Invalid overlay parameter");
+            }
+        }
+    }
+
+    if url_parts.len() >= 6 {
+        match wrl::PreviewFit::from_name(url_parts[5]) {
+            Some(f) => {
+                fit = f;
+            }
+            None => {
+                return create_error_response(400, "Invalid fit parameter");
+            }
+        }
+    }
+
+    let cache_key = PreviewCacheKey {
+        map_hash_id,
+        kind: PreviewKind::Bigmap,
+        width,
+        height,
+        fit,
+        scanline: false,
+        overlay,
+    };
+    let etag = cache_key.etag();
+
+    if matches_etag(if_none_match, &etag) {
+        return create_not_modified_response(&etag);
+    }
+
+    if let Some(image_data) = get_cached_preview(&cache_key) {
+        return create_cached_response(image_data, "image/bmp", &etag);
+    }
+
+    let options = wrl::PreviewOptions {
+        target_pixel_width: width,
+        target_pixel_height: height,
+        scanline: false,
+        overlay,
+        fit,
+    };
+    let (minimap_pixels, output_width, output_height) =
+        generate_bigmap_preview(map_hash_id, options).unwrap_or_else(|e| {
+            log::error!(
+                "Failed to generate bigmap preview for map {}: {}",
+                map_hash_id,
+                e
+            );
+            (Vec::new(), 0, 0)
+        });
 
     if minimap_pixels.len() == 0 {
         return create_error_response(500, "Minimap pixel buffer is empty");
     }
 
-    let image_data = bmp::rgba_to_bmp32(&minimap_pixels, size as u32, size as u32)
+    let image_data = bmp::rgba_to_bmp32(&minimap_pixels, output_width as u32, output_height as u32)
         .expect("Failed to generate BMP image");
 
-    return http::Response::builder()
-        .header("Content-Type", "image/bmp")
-        .header("Access-Control-Allow-Origin", "*")
-        .header("Access-Control-Allow-Methods", "GET, POST, OPTIONS")
-        .header(
-            "Access-Control-Allow-Headers",
-            "Content-Type, Authorization",
-        )
-        .body(image_data)
-        .expect("Failed to build response");
+    store_cached_preview(&cache_key, &image_data);
+
+    create_cached_response(image_data, "image/bmp", &etag)
 }
@@ -2,11 +2,42 @@ use std::path::PathBuf;
 
 use tauri::http;
 
-use crate::{GLOBAL_APP_STATE, internal_server::create_error_response::create_error_response};
+use crate::{
+    GLOBAL_APP_STATE,
+    internal_server::{
+        create_cached_response::{
+            create_cached_response, create_not_modified_response, matches_etag,
+        },
+        create_error_response::create_error_response,
+    },
+    preview_cache::{PreviewCacheKey, PreviewKind, get_cached_preview, store_cached_preview},
+};
 
-pub fn get_wrl_minimap_request(url_parts: Vec<&str>) -> http::Response<Vec<u8>> {
+pub fn get_wrl_minimap_request(
+    url_parts: Vec<&str>,
+    if_none_match: Option<&str>,
+) -> http::Response<Vec<u8>> {
     let map_hash_id = url_parts[2];
 
+    let cache_key = PreviewCacheKey {
+        map_hash_id,
+        kind: PreviewKind::Minimap,
+        width: 0,
+        height: 0,
+        fit: wrl::PreviewFit::default(),
+        scanline: false,
+        overlay: wrl::PreviewOverlay::None,
+    };
+    let etag = cache_key.etag();
+
+    if matches_etag(if_none_match, &etag) {
+        return create_not_modified_response(&etag);
+    }
+
+    if let Some(image_data) = get_cached_preview(&cache_key) {
+        return create_cached_response(image_data, "image/bmp", &etag);
+    }
+
     let map_metadata = GLOBAL_APP_STATE.get_map_metadata(map_hash_id);
 
     if map_metadata.is_none() {
@@ -41,14 +72,7 @@ pub fn get_wrl_minimap_request(url_parts: Vec<&str>) -> http::Response<Vec<u8>>
     let image_data = bmp::rgba_to_bmp32(&minimap_pixels, width as u32, height as u32)
         .expect("Failed to generate BMP image");
 
-    return http::Response::builder()
-        .header("Content-Type", "image/bmp")
-        .header("Access-Control-Allow-Origin", "*")
-        .header("Access-Control-Allow-Methods", "GET, POST, OPTIONS")
-        .header(
-            "Access-Control-Allow-Headers",
-            "Content-Type, Authorization",
-        )
-        .body(image_data)
-        .expect("Failed to build response");
+    store_cached_preview(&cache_key, &image_data);
+
+    create_cached_response(image_data, "image/bmp", &etag)
 }
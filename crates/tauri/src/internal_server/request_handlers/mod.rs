@@ -4,6 +4,9 @@ pub use get_file_request::*;
 pub mod get_res_image_request;
 pub use get_res_image_request::*;
 
+pub mod get_wrl_bigmap_animation_request;
+pub use get_wrl_bigmap_animation_request::*;
+
 pub mod get_wrl_bigmap_request;
 pub use get_wrl_bigmap_request::*;
 
@@ -9,6 +9,7 @@ mod internal_server;
 mod load_known_maps_info;
 use load_known_maps_info::load_known_maps_info;
 mod log_file;
+mod preview_cache;
 
 use app_state::AppState;
 use commands::*;
@@ -96,13 +97,18 @@ pub fn run() {
         })
         .invoke_handler(tauri::generate_handler![
             open_dir_path_in_file_explorer_command,
+            analyze_map_terrain_command,
             archive_map_and_saves_command,
+            export_tile_atlas_command,
+            find_similar_maps_command,
+            fix_tail_header_hash_command,
             generate_bigmap_preview_command,
             get_app_state_command,
             get_archived_maps_and_saves_command,
             get_installed_maps_and_saves_command,
             install_imported_map_command,
             is_setup_required_command,
+            lint_wrl_file_command,
             open_devtools_command,
             read_archived_maps_metadata_command,
             read_installed_maps_metadata_command,
@@ -112,6 +118,7 @@ pub fn run() {
             set_app_paths_command,
             verify_dir_path_command,
             verify_game_path_command,
+            verify_tail_header_command,
         ])
         .register_uri_scheme_protocol("be", move |_app, request| {
             internal_server::handle_request(request)
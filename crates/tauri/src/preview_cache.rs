@@ -0,0 +1,231 @@
+use std::fs::{self, File};
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use std::time::SystemTime;
+
+use crate::GLOBAL_APP_STATE;
+
+/// Directory inside the app data dir which holds the rendered previews.
+const PREVIEW_CACHE_DIR_NAME: &str = "preview_cache";
+
+/// Total size of the cached previews above which the least recently used ones are evicted.
+const PREVIEW_CACHE_MAX_SIZE: u64 = 64 * 1024 * 1024;
+
+/// Number of maps whose tile mipmaps are kept in memory.
+const TILE_MIPMAPS_CACHE_MAX_MAPS: usize = 4;
+
+lazy_static::lazy_static! {
+    /// Tile mipmaps by map hash, the most recently used last.
+    static ref TILE_MIPMAPS_CACHE: Mutex<Vec<(String, Arc<wrl::TileMipmaps>)>> = Mutex::new(Vec::new());
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PreviewKind {
+    Bigmap,
+    /// Animated bigmap preview of one full palette cycle.
+    BigmapAnimation,
+    Minimap,
+}
+
+impl PreviewKind {
+    pub fn name(&self) -> &'static str {
+        match self {
+            PreviewKind::Bigmap => "bigmap",
+            PreviewKind::BigmapAnimation => "bigmap_animation",
+            PreviewKind::Minimap => "minimap",
+        }
+    }
+}
+
+/// Identifies a rendered preview. Previews are immutable for a given key,
+/// because the map hash changes together with the map content.
+#[derive(Debug, Clone, Copy)]
+pub struct PreviewCacheKey<'a> {
+    pub map_hash_id: &'a str,
+    pub kind: PreviewKind,
+    /// Target width in pixels, 0 for previews rendered at their native size.
+    pub width: usize,
+    /// Target height in pixels, 0 for previews rendered at their native size.
+    pub height: usize,
+    pub fit: wrl::PreviewFit,
+    pub scanline: bool,
+    pub overlay: wrl::PreviewOverlay,
+}
+
+impl PreviewCacheKey<'_> {
+    fn entry_name(&self) -> String {
+        format!(
+            "{}_{}_{}x{}_{}_{}_{}",
+            self.map_hash_id,
+            self.kind.name(),
+            self.width,
+            self.height,
+            self.fit.name(),
+            self.scanline as u8,
+            self.overlay.name()
+        )
+    }
+
+    /// Returns the cache file path, or `None` if the hash can not be used as a file name.
+    fn file_path(&self) -> Option<PathBuf> {
+        if self.map_hash_id.is_empty()
+            || !self.map_hash_id.chars().all(|c| c.is_ascii_alphanumeric())
+        {
+            return None;
+        }
+
+        Some(preview_cache_dir_path().join(format!("{}.bin", self.entry_name())))
+    }
+
+    /// Quoted entity tag of the preview, as used by the `ETag` header.
+    pub fn etag(&self) -> String {
+        format!("\"{}\"", self.entry_name())
+    }
+}
+
+fn preview_cache_dir_path() -> PathBuf {
+    GLOBAL_APP_STATE
+        .app_data_dir_path()
+        .join(PREVIEW_CACHE_DIR_NAME)
+}
+
+/// Returns the cached preview and marks it as recently used.
+pub fn get_cached_preview(key: &PreviewCacheKey) -> Option<Vec<u8>> {
+    let file_path = key.file_path()?;
+    let data = fs::read(&file_path).ok()?;
+
+    // The modification time doubles as the last access time for the LRU eviction.
+    if let Err(e) = File::options()
+        .append(true)
+        .open(&file_path)
+        .and_then(|file| file.set_modified(SystemTime::now()))
+    {
+        log::warn!(
+            "Failed to update access time of cached preview: {}: {}",
+            file_path.display(),
+            e
+        );
+    }
+
+    Some(data)
+}
+
+/// Stores the preview in the cache, evicting the least recently used previews above the size cap.
+/// Failures are logged only, the preview is still served without the cache.
+pub fn store_cached_preview(key: &PreviewCacheKey, data: &[u8]) {
+    let Some(file_path) = key.file_path() else {
+        return;
+    };
+
+    if let Err(e) = fs::create_dir_all(preview_cache_dir_path()) {
+        log::error!(
+            "Failed to create preview cache directory: {}",
+            preview_cache_dir_path().display()
+        );
+        log::error!("{}", e);
+        return;
+    }
+
+    if let Err(e) = fs::write(&file_path, data) {
+        log::error!("Failed to write cached preview: {}", file_path.display());
+        log::error!("{}", e);
+        let _ = fs::remove_file(&file_path);
+        return;
+    }
+
+    evict_least_recently_used_previews(PREVIEW_CACHE_MAX_SIZE);
+}
+
+/// Returns the tile mipmaps of the map, building them on a cache miss.
+/// Mipmaps only depend on the map content, so they are kept in memory by the map hash.
+pub fn get_tile_mipmaps(
+    map_hash_id: &str,
+    build: impl FnOnce() -> wrl::TileMipmaps,
+) -> Arc<wrl::TileMipmaps> {
+    {
+        let mut cache = TILE_MIPMAPS_CACHE.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some(position) = cache.iter().position(|(hash_id, _)| hash_id == map_hash_id) {
+            let entry = cache.remove(position);
+            let mipmaps = entry.1.clone();
+            cache.push(entry);
+            return mipmaps;
+        }
+    }
+
+    // Built without holding the lock, so previews of other maps are not blocked.
+    let mipmaps = Arc::new(build());
+
+    let mut cache = TILE_MIPMAPS_CACHE.lock().unwrap_or_else(|e| e.into_inner());
+    cache.retain(|(hash_id, _)| hash_id != map_hash_id);
+    cache.push((map_hash_id.to_string(), mipmaps.clone()));
+    if cache.len() > TILE_MIPMAPS_CACHE_MAX_MAPS {
+        cache.remove(0);
+    }
+
+    mipmaps
+}
+
+/// Removes every cached preview of the map, e.g. after its content and so its hash changed.
+pub fn remove_cached_previews(map_hash_id: &str) {
+    TILE_MIPMAPS_CACHE
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .retain(|(hash_id, _)| hash_id != map_hash_id);
+
+    let Ok(entries) = fs::read_dir(preview_cache_dir_path()) else {
+        return;
+    };
+
+    let prefix = format!("{}_", map_hash_id);
+    for entry in entries.flatten() {
+        if !entry.file_name().to_string_lossy().starts_with(&prefix) {
+            continue;
+        }
+        if let Err(e) = fs::remove_file(entry.path()) {
+            log::warn!(
+                "Failed to remove cached preview: {}: {}",
+                entry.path().display(),
+                e
+            );
+        }
+    }
+}
+
+fn evict_least_recently_used_previews(max_size: u64) {
+    let Ok(entries) = fs::read_dir(preview_cache_dir_path()) else {
+        return;
+    };
+
+    let mut cached_files: Vec<(PathBuf, u64, SystemTime)> = entries
+        .flatten()
+        .filter_map(|entry| {
+            let metadata = entry.metadata().ok()?;
+            if !metadata.is_file() {
+                return None;
+            }
+            let last_used = metadata.modified().unwrap_or(SystemTime::UNIX_EPOCH);
+            Some((entry.path(), metadata.len(), last_used))
+        })
+        .collect();
+
+    let mut total_size: u64 = cached_files.iter().map(|(_, size, _)| size).sum();
+    if total_size <= max_size {
+        return;
+    }
+
+    cached_files.sort_by_key(|(_, _, last_used)| *last_used);
+
+    for (file_path, size, _) in cached_files {
+        if total_size <= max_size {
+            break;
+        }
+        match fs::remove_file(&file_path) {
+            Ok(()) => total_size -= size,
+            Err(e) => log::warn!(
+                "Failed to evict cached preview: {}: {}",
+                file_path.display(),
+                e
+            ),
+        }
+    }
+}
@@ -0,0 +1,433 @@
+use std::cmp::Reverse;
+use std::collections::VecDeque;
+
+use serde::Serialize;
+
+use crate::error::WrlError;
+use crate::passability::Passability;
+use crate::structs::WRLFile;
+
+/// Share of each terrain class in the map, in percent of all cells.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct TerrainComposition {
+	pub land: f64,
+	pub water: f64,
+	pub coast: f64,
+	pub blocked: f64,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Landmass {
+	/// Number of land and coast cells.
+	pub cells: usize,
+	/// Width of the narrowest land passage bounded by water on both sides whose removal
+	/// splits the landmass, `None` when the landmass has no such passage.
+	pub choke_point_width: Option<u16>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum MapKind {
+	/// Most of the land forms a single landmass.
+	Continental,
+	/// The land is split between several landmasses, none holding most of it.
+	Islands,
+	/// The map has no land at all.
+	Water,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct TerrainAnalysis {
+	pub composition: TerrainComposition,
+	/// Landmasses sorted from the largest.
+	pub landmasses: Vec<Landmass>,
+	/// Sizes of the connected water regions in cells, sorted from the largest.
+	pub sea_regions: Vec<usize>,
+	pub island_count: usize,
+	pub largest_landmass: usize,
+	pub map_kind: MapKind,
+}
+
+/// Computes the terrain composition and the connectivity of land and sea.
+/// Land and coast cells form landmasses, water cells form sea regions,
+/// both connected through cell edges. Blocked cells belong to neither.
+pub fn analyze_terrain(wrl_file: &WRLFile) -> Result<TerrainAnalysis, WrlError> {
+	let passability = wrl_file.passability_map()?;
+	let width = wrl_file.width as usize;
+	let height = wrl_file.height as usize;
+	let cell_count = passability.len();
+
+	let percent = |class: Passability| {
+		let count = passability.iter().filter(|&&p| p == class).count();
+		if cell_count == 0 { 0.0 } else { count as f64 * 100.0 / cell_count as f64 }
+	};
+	let composition = TerrainComposition {
+		land: percent(Passability::Land),
+		water: percent(Passability::Water),
+		coast: percent(Passability::Coast),
+		blocked: percent(Passability::Blocked),
+	};
+
+	let is_land: Vec<bool> = passability
+		.iter()
+		.map(|&p| p == Passability::Land || p == Passability::Coast)
+		.collect();
+	let is_water: Vec<bool> = passability.iter().map(|&p| p == Passability::Water).collect();
+
+	let (land_regions, land_region_count) = label_regions(&is_land, width, height);
+	let (water_regions, water_region_count) = label_regions(&is_water, width, height);
+
+	let mut landmasses: Vec<Landmass> = (0..land_region_count)
+		.map(|_| Landmass { cells: 0, choke_point_width: None })
+		.collect();
+	for region in land_regions.iter().flatten() {
+		landmasses[*region].cells += 1;
+	}
+	for (region, choke_point_width) in find_choke_points(&land_regions, &is_water, width, height) {
+		let landmass = &mut landmasses[region];
+		landmass.choke_point_width = Some(landmass.choke_point_width.map_or(choke_point_width, |w| w.min(choke_point_width)));
+	}
+	landmasses.sort_by_key(|landmass| Reverse(landmass.cells));
+
+	let mut sea_regions = vec![0usize; water_region_count];
+	for region in water_regions.iter().flatten() {
+		sea_regions[*region] += 1;
+	}
+	sea_regions.sort_by_key(|&cells| Reverse(cells));
+
+	let land_cells: usize = landmasses.iter().map(|landmass| landmass.cells).sum();
+	let largest_landmass = landmasses.first().map_or(0, |landmass| landmass.cells);
+	let map_kind = if land_cells == 0 {
+		MapKind::Water
+	} else if largest_landmass * 2 >= land_cells {
+		MapKind::Continental
+	} else {
+		MapKind::Islands
+	};
+
+	Ok(TerrainAnalysis {
+		composition,
+		island_count: landmasses.len(),
+		largest_landmass,
+		landmasses,
+		sea_regions,
+		map_kind,
+	})
+}
+
+/// Labels the edge-connected regions of matching cells with a flood fill.
+fn label_regions(is_member: &[bool], width: usize, height: usize) -> (Vec<Option<usize>>, usize) {
+	let mut regions: Vec<Option<usize>> = vec![None; is_member.len()];
+	let mut region_count = 0;
+	let mut queue = VecDeque::new();
+
+	for start in 0..is_member.len() {
+		if !is_member[start] || regions[start].is_some() {
+			continue;
+		}
+
+		regions[start] = Some(region_count);
+		queue.push_back(start);
+
+		while let Some(cell) = queue.pop_front() {
+			for neighbor in neighbors(cell, width, height).into_iter().flatten() {
+				if is_member[neighbor] && regions[neighbor].is_none() {
+					regions[neighbor] = Some(region_count);
+					queue.push_back(neighbor);
+				}
+			}
+		}
+
+		region_count += 1;
+	}
+
+	(regions, region_count)
+}
+
+/// Returns the edge neighbors of the cell which are inside the map.
+fn neighbors(cell: usize, width: usize, height: usize) -> [Option<usize>; 4] {
+	let x = cell % width;
+	let y = cell / width;
+	[
+		(x > 0).then(|| cell - 1),
+		(x + 1 < width).then(|| cell + 1),
+		(y > 0).then(|| cell - width),
+		(y + 1 < height).then(|| cell + width),
+	]
+}
+
+/// Finds horizontal and vertical runs of land with water at both ends, which have land on both
+/// sides across the run and link wider land, see `is_choke_point`. Such a run cuts the landmass
+/// at its length, so its length is the width of the passage. Cross-sections of solid land
+/// or of a tapering peninsula are not choke points, the land on one side is not wider.
+fn find_choke_points(
+	land_regions: &[Option<usize>],
+	is_water: &[bool],
+	width: usize,
+	height: usize,
+) -> Vec<(usize, u16)> {
+	let mut choke_points = Vec::new();
+
+	// Horizontal runs, walking along x with neighbors above and below.
+	for y in 1..height.saturating_sub(1) {
+		let cell = |x: usize| y * width + x;
+		let mut x = 1;
+		while x + 1 < width {
+			if land_regions[cell(x)].is_none() || !is_water[cell(x - 1)] {
+				x += 1;
+				continue;
+			}
+
+			let run_start = x;
+			while x < width && land_regions[cell(x)].is_some() {
+				x += 1;
+			}
+			if x < width && is_water[cell(x)] {
+				let has_land_above = (run_start..x).any(|run_x| land_regions[cell(run_x) - width].is_some());
+				let has_land_below = (run_start..x).any(|run_x| land_regions[cell(run_x) + width].is_some());
+				let run: Vec<usize> = (run_start..x).map(cell).collect();
+				if has_land_above && has_land_below && is_choke_point(&run, true, land_regions, width, height) {
+					choke_points.push((land_regions[cell(run_start)].unwrap(), run.len() as u16));
+				}
+			}
+		}
+	}
+
+	// Vertical runs, walking along y with neighbors on the left and right.
+	for x in 1..width.saturating_sub(1) {
+		let cell = |y: usize| y * width + x;
+		let mut y = 1;
+		while y + 1 < height {
+			if land_regions[cell(y)].is_none() || !is_water[cell(y - 1)] {
+				y += 1;
+				continue;
+			}
+
+			let run_start = y;
+			while y < height && land_regions[cell(y)].is_some() {
+				y += 1;
+			}
+			if y < height && is_water[cell(y)] {
+				let has_land_left = (run_start..y).any(|run_y| land_regions[cell(run_y) - 1].is_some());
+				let has_land_right = (run_start..y).any(|run_y| land_regions[cell(run_y) + 1].is_some());
+				let run: Vec<usize> = (run_start..y).map(cell).collect();
+				if has_land_left && has_land_right && is_choke_point(&run, false, land_regions, width, height) {
+					choke_points.push((land_regions[cell(run_start)].unwrap(), run.len() as u16));
+				}
+			}
+		}
+	}
+
+	choke_points
+}
+
+/// Checks whether the run is a passage between wider land. Removing it must split the landmass,
+/// and every part left must hold a run along the same direction longer than the removed one.
+/// The landmass is connected, so every part left after the removal touches the run.
+fn is_choke_point(
+	run: &[usize],
+	horizontal: bool,
+	land_regions: &[Option<usize>],
+	width: usize,
+	height: usize,
+) -> bool {
+	let region = land_regions[run[0]];
+	let is_part_cell = |cell: usize| land_regions[cell] == region && !run.contains(&cell);
+	let mut parts: Vec<Option<usize>> = vec![None; land_regions.len()];
+
+	let mut part_count = 0;
+	let mut queue = VecDeque::new();
+	for &cell in run {
+		for start in neighbors(cell, width, height).into_iter().flatten() {
+			if !is_part_cell(start) || parts[start].is_some() {
+				continue;
+			}
+
+			parts[start] = Some(part_count);
+			queue.push_back(start);
+			while let Some(part_cell) = queue.pop_front() {
+				for neighbor in neighbors(part_cell, width, height).into_iter().flatten() {
+					if is_part_cell(neighbor) && parts[neighbor].is_none() {
+						parts[neighbor] = Some(part_count);
+						queue.push_back(neighbor);
+					}
+				}
+			}
+			part_count += 1;
+		}
+	}
+	if part_count < 2 {
+		return false;
+	}
+
+	// Neighbor indices of the previous and next cell along the run direction.
+	let (previous, next) = if horizontal { (0, 1) } else { (2, 3) };
+	let mut widest = vec![0; part_count];
+	for cell in 0..parts.len() {
+		let Some(part) = parts[cell] else {
+			continue;
+		};
+		if neighbors(cell, width, height)[previous].is_some_and(|previous_cell| parts[previous_cell] == Some(part)) {
+			continue;
+		}
+
+		let mut length = 1;
+		let mut run_cell = cell;
+		while let Some(next_cell) = neighbors(run_cell, width, height)[next].filter(|&c| parts[c] == Some(part)) {
+			length += 1;
+			run_cell = next_cell;
+		}
+		widest[part] = widest[part].max(length);
+	}
+
+	widest.iter().all(|&part_width| part_width > run.len())
+}
+
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	use crate::consts::TILE_DATA_SIZE;
+	use crate::structs::WRLFileHeader;
+	use crate::run_test;
+
+	/// Builds a map from rows of `L` (land), `W` (water), `C` (coast) and `B` (blocked) cells.
+	fn map_from_rows(rows: &[&str]) -> WRLFile {
+		let width = rows[0].len() as u16;
+		let height = rows.len() as u16;
+		let bigmap: Vec<u16> = rows
+			.iter()
+			.flat_map(|row| row.chars())
+			.map(|c| match c {
+				'L' => 0,
+				'W' => 1,
+				'C' => 2,
+				_ => 3,
+			})
+			.collect();
+
+		WRLFile {
+			header: WRLFileHeader::default(),
+			width,
+			height,
+			minimap: vec![0; bigmap.len()],
+			bigmap,
+			tile_count: 4,
+			tiles: vec![0; 4 * TILE_DATA_SIZE],
+			palette: vec![0; 256 * 3],
+			pass_table: vec![0, 1, 2, 3],
+		}
+	}
+
+	#[test]
+	fn test_analyze_terrain_continental() {
+		run_test!({
+			// Arrange
+			let wrl_file = map_from_rows(&[
+				"WWWWWWWW",
+				"WLLLWWLW",
+				"WLLLWWWW",
+				"WWLWWWWW",
+				"WLLLCWBW",
+				"WLLLLWWW",
+				"WWWWWWWW",
+				"WWWWWWWW",
+			]);
+
+			// Act
+			let analysis = analyze_terrain(&wrl_file).unwrap();
+
+			// Assert
+			assert_eq!(analysis.composition.land, 23.4375);
+			assert_eq!(analysis.composition.coast, 1.5625);
+			assert_eq!(analysis.composition.blocked, 1.5625);
+			assert_eq!(analysis.composition.water, 73.4375);
+			assert_eq!(analysis.island_count, 2);
+			assert_eq!(analysis.largest_landmass, 15);
+			assert_eq!(analysis.landmasses[0], Landmass { cells: 15, choke_point_width: Some(1) });
+			assert_eq!(analysis.landmasses[1], Landmass { cells: 1, choke_point_width: None });
+			assert_eq!(analysis.sea_regions, vec![47]);
+			assert_eq!(analysis.map_kind, MapKind::Continental);
+
+			let logs = get_captured_logs();
+			assert!(logs.is_empty());
+		});
+	}
+
+	#[test]
+	fn test_analyze_terrain_islands() {
+		run_test!({
+			// Arrange
+			let wrl_file = map_from_rows(&[
+				"LLWWLL",
+				"LLWWLL",
+				"WWWWWW",
+				"LLWWLW",
+			]);
+
+			// Act
+			let analysis = analyze_terrain(&wrl_file).unwrap();
+
+			// Assert
+			assert_eq!(analysis.island_count, 4);
+			assert_eq!(analysis.largest_landmass, 4);
+			assert_eq!(analysis.sea_regions, vec![13]);
+			assert_eq!(analysis.map_kind, MapKind::Islands);
+
+			let logs = get_captured_logs();
+			assert!(logs.is_empty());
+		});
+	}
+
+	#[test]
+	fn test_analyze_terrain_solid_landmass() {
+		run_test!({
+			// Arrange
+			let wrl_file = map_from_rows(&[
+				"WWWWWWW",
+				"WLLLLLW",
+				"WLLLLLW",
+				"WLLLLLW",
+				"WLLLLLW",
+				"WLLLLLW",
+				"WWWWWWW",
+			]);
+
+			// Act
+			let analysis = analyze_terrain(&wrl_file).unwrap();
+
+			// Assert
+			assert_eq!(analysis.landmasses, vec![Landmass { cells: 25, choke_point_width: None }]);
+			assert_eq!(analysis.map_kind, MapKind::Continental);
+
+			let logs = get_captured_logs();
+			assert!(logs.is_empty());
+		});
+	}
+
+	#[test]
+	fn test_analyze_terrain_no_land() {
+		run_test!({
+			// Arrange
+			let wrl_file = map_from_rows(&[
+				"WWWW",
+				"WWBW",
+				"WWWW",
+			]);
+
+			// Act
+			let analysis = analyze_terrain(&wrl_file).unwrap();
+
+			// Assert
+			assert!(analysis.landmasses.is_empty());
+			assert_eq!(analysis.island_count, 0);
+			assert_eq!(analysis.largest_landmass, 0);
+			assert_eq!(analysis.sea_regions, vec![11]);
+			assert_eq!(analysis.map_kind, MapKind::Water);
+
+			let logs = get_captured_logs();
+			assert!(logs.is_empty());
+		});
+	}
+}
@@ -0,0 +1,129 @@
+use std::cmp::Ordering;
+
+use serde::Serialize;
+
+use crate::analysis::map_fingerprint::MapFingerprint;
+
+/// Similarity above which two maps are considered variants of the same map.
+pub const SIMILAR_MAP_THRESHOLD: f64 = 0.9;
+
+/// Group of maps which are probably variants of the same map.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct MapCluster {
+	/// Ids of the maps in the cluster, in input order.
+	pub map_ids: Vec<String>,
+	/// Lowest similarity between two maps of the cluster, from 0 to 1.
+	pub similarity: f64,
+}
+
+/// Groups the maps into clusters of similar maps.
+/// Maps are linked when their similarity reaches the threshold, and a cluster holds
+/// every map reachable through such links. Maps without a similar map are left out.
+/// Clusters are sorted from the most similar.
+pub fn cluster_similar_maps(maps: &[(String, MapFingerprint)], threshold: f64) -> Vec<MapCluster> {
+	let map_count = maps.len();
+	let mut similarities = vec![1.0f64; map_count * map_count];
+	let mut parents: Vec<usize> = (0..map_count).collect();
+
+	for a in 0..map_count {
+		for b in a + 1..map_count {
+			let similarity = maps[a].1.similarity(&maps[b].1);
+			similarities[a * map_count + b] = similarity;
+			similarities[b * map_count + a] = similarity;
+
+			if similarity >= threshold {
+				let root_a = find_root(&mut parents, a);
+				let root_b = find_root(&mut parents, b);
+				parents[root_a.max(root_b)] = root_a.min(root_b);
+			}
+		}
+	}
+
+	let mut members: Vec<Vec<usize>> = vec![Vec::new(); map_count];
+	for map in 0..map_count {
+		let root = find_root(&mut parents, map);
+		members[root].push(map);
+	}
+
+	let mut clusters: Vec<MapCluster> = members
+		.into_iter()
+		.filter(|cluster_members| cluster_members.len() > 1)
+		.map(|cluster_members| {
+			let mut similarity = 1.0f64;
+			for (i, &a) in cluster_members.iter().enumerate() {
+				for &b in &cluster_members[i + 1..] {
+					similarity = similarity.min(similarities[a * map_count + b]);
+				}
+			}
+
+			MapCluster {
+				map_ids: cluster_members.iter().map(|&map| maps[map].0.clone()).collect(),
+				similarity,
+			}
+		})
+		.collect();
+
+	clusters.sort_by(|a, b| b.similarity.partial_cmp(&a.similarity).unwrap_or(Ordering::Equal));
+
+	clusters
+}
+
+fn find_root(parents: &mut [usize], map: usize) -> usize {
+	let mut root = map;
+	while parents[root] != root {
+		root = parents[root];
+	}
+
+	let mut node = map;
+	while parents[node] != root {
+		let parent = parents[node];
+		parents[node] = root;
+		node = parent;
+	}
+
+	root
+}
+
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use std::path::PathBuf;
+
+	use crate::run_test;
+	use crate::{fingerprint_map, read_wrl_file};
+
+	#[test]
+	fn test_cluster_similar_maps() {
+		run_test!({
+			// Arrange
+			let wrl_file = read_wrl_file(&PathBuf::from("test_files/reference/REF.WRL")).unwrap();
+			let mut edited_wrl_file = wrl_file.clone();
+			edited_wrl_file.bigmap[0] = 1;
+			let mut twice_edited_wrl_file = edited_wrl_file.clone();
+			twice_edited_wrl_file.bigmap[1] = 3;
+			let mut reordered_wrl_file = wrl_file.clone();
+			reordered_wrl_file.bigmap.reverse();
+			reordered_wrl_file.minimap.reverse();
+
+			let maps = vec![
+				("original".to_string(), fingerprint_map(&wrl_file)),
+				("reordered".to_string(), fingerprint_map(&reordered_wrl_file)),
+				("edited".to_string(), fingerprint_map(&edited_wrl_file)),
+				("twice_edited".to_string(), fingerprint_map(&twice_edited_wrl_file)),
+			];
+
+			// Act
+			let clusters = cluster_similar_maps(&maps, SIMILAR_MAP_THRESHOLD);
+
+			// Assert
+			assert_eq!(clusters.len(), 1);
+			assert_eq!(clusters[0].map_ids, vec!["original", "edited", "twice_edited"]);
+			// The original and the twice edited map differ in 2 of 256 cells.
+			assert!((clusters[0].similarity - (0.75 * 254.0 / 256.0 + 0.25)).abs() < 1e-9);
+
+			let logs = get_captured_logs();
+			assert!(logs.is_empty());
+		});
+	}
+}
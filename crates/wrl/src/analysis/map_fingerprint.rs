@@ -0,0 +1,217 @@
+use std::collections::HashMap;
+
+use crate::consts::TILE_DATA_SIZE;
+use crate::file::wrl_view::WrlView;
+use crate::structs::WRLFile;
+
+/// Weight of the matching cells in the map similarity, the rest is the minimap perceptual hash.
+const CELL_SIMILARITY_WEIGHT: f64 = 0.75;
+
+/// Size of the grayscale minimap thumbnail used by the perceptual hash.
+/// It is one pixel wider than high, so every row gives 8 horizontal differences.
+const PERCEPTUAL_HASH_WIDTH: usize = 9;
+const PERCEPTUAL_HASH_HEIGHT: usize = 8;
+
+/// Similarity fingerprint of a map, which unlike the file hash
+/// survives small edits like a few changed cells or a re-saved file.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MapFingerprint {
+	pub width: u16,
+	pub height: u16,
+	/// Hash of the tile content of every cell, in bigmap order.
+	/// Tiles are hashed by their colors, so the same terrain matches across tile and palette orders.
+	pub cell_hashes: Vec<u64>,
+	/// Difference hash of the minimap brightness.
+	pub minimap_hash: u64,
+}
+
+impl MapFingerprint {
+	/// Share of cells with the same tile content, from 0 to 1.
+	/// Maps of different size have no matching cells.
+	pub fn cell_similarity(&self, other: &MapFingerprint) -> f64 {
+		if self.width != other.width || self.height != other.height || self.cell_hashes.is_empty() {
+			return 0.0;
+		}
+
+		let matching_cells = self
+			.cell_hashes
+			.iter()
+			.zip(&other.cell_hashes)
+			.filter(|(a, b)| a == b)
+			.count();
+
+		matching_cells as f64 / self.cell_hashes.len() as f64
+	}
+
+	/// Share of matching minimap perceptual hash bits, from 0 to 1.
+	pub fn minimap_similarity(&self, other: &MapFingerprint) -> f64 {
+		1.0 - (self.minimap_hash ^ other.minimap_hash).count_ones() as f64 / 64.0
+	}
+
+	/// Weighted similarity of the two maps, from 0 for unrelated maps to 1 for the same terrain.
+	pub fn similarity(&self, other: &MapFingerprint) -> f64 {
+		CELL_SIMILARITY_WEIGHT * self.cell_similarity(other)
+			+ (1.0 - CELL_SIMILARITY_WEIGHT) * self.minimap_similarity(other)
+	}
+}
+
+/// Computes the similarity fingerprint of the map.
+/// Cells referencing missing tiles get a zero hash.
+pub fn fingerprint_map(wrl_file: &WRLFile) -> MapFingerprint {
+	fingerprint_map_sections(
+		wrl_file.width,
+		wrl_file.height,
+		&wrl_file.bigmap,
+		&wrl_file.tiles,
+		&wrl_file.palette,
+		&wrl_file.minimap,
+	)
+}
+
+/// Computes the similarity fingerprint of a mapped file.
+/// Only the tiles referenced by the bigmap are read.
+pub fn fingerprint_wrl_view(wrl_view: &WrlView) -> MapFingerprint {
+	fingerprint_map_sections(
+		wrl_view.width(),
+		wrl_view.height(),
+		&wrl_view.bigmap(),
+		wrl_view.tiles(),
+		wrl_view.palette(),
+		wrl_view.minimap(),
+	)
+}
+
+fn fingerprint_map_sections(
+	width: u16,
+	height: u16,
+	bigmap: &[u16],
+	tiles: &[u8],
+	palette: &[u8],
+	minimap: &[u8],
+) -> MapFingerprint {
+	let mut tile_hashes: HashMap<u16, u64> = HashMap::new();
+	let cell_hashes = bigmap
+		.iter()
+		.map(|&tile_index| {
+			*tile_hashes.entry(tile_index).or_insert_with(|| {
+				let tile_start = tile_index as usize * TILE_DATA_SIZE;
+				match tiles.get(tile_start..tile_start + TILE_DATA_SIZE) {
+					Some(tile) => hash_tile_colors(tile, palette),
+					None => 0,
+				}
+			})
+		})
+		.collect();
+
+	MapFingerprint {
+		width,
+		height,
+		cell_hashes,
+		minimap_hash: minimap_perceptual_hash(minimap, palette, width as usize, height as usize),
+	}
+}
+
+fn hash_tile_colors(tile: &[u8], palette: &[u8]) -> u64 {
+	let mut hasher = blake3::Hasher::new();
+	for &color_index in tile {
+		let palette_index = color_index as usize * 3;
+		let color = palette.get(palette_index..palette_index + 3).unwrap_or(&[0, 0, 0]);
+		hasher.update(color);
+	}
+
+	let hash = hasher.finalize();
+	let mut bytes = [0u8; 8];
+	bytes.copy_from_slice(&hash.as_bytes()[..8]);
+	u64::from_le_bytes(bytes)
+}
+
+/// Difference hash of the minimap: the brightness is averaged down to a 9x8 thumbnail
+/// and every bit tells whether a thumbnail pixel is darker than its right neighbor.
+fn minimap_perceptual_hash(minimap: &[u8], palette: &[u8], width: usize, height: usize) -> u64 {
+	if width == 0 || height == 0 || minimap.len() < width * height {
+		return 0;
+	}
+
+	let brightness = |color_index: u8| -> u32 {
+		let palette_index = color_index as usize * 3;
+		match palette.get(palette_index..palette_index + 3) {
+			Some(color) => (color[0] as u32 * 299 + color[1] as u32 * 587 + color[2] as u32 * 114) / 1000,
+			None => 0,
+		}
+	};
+
+	let range = |index: usize, count: usize, size: usize| {
+		let start = index * size / count;
+		let end = ((index + 1) * size / count).max(start + 1).min(size);
+		start.min(size - 1)..end
+	};
+
+	let mut thumbnail = [[0u32; PERCEPTUAL_HASH_WIDTH]; PERCEPTUAL_HASH_HEIGHT];
+	for (thumbnail_y, thumbnail_row) in thumbnail.iter_mut().enumerate() {
+		for (thumbnail_x, thumbnail_pixel) in thumbnail_row.iter_mut().enumerate() {
+			let mut sum = 0u32;
+			let mut count = 0u32;
+			for y in range(thumbnail_y, PERCEPTUAL_HASH_HEIGHT, height) {
+				for x in range(thumbnail_x, PERCEPTUAL_HASH_WIDTH, width) {
+					sum += brightness(minimap[y * width + x]);
+					count += 1;
+				}
+			}
+			*thumbnail_pixel = sum / count.max(1);
+		}
+	}
+
+	let mut hash = 0u64;
+	for thumbnail_row in &thumbnail {
+		for x in 0..PERCEPTUAL_HASH_WIDTH - 1 {
+			hash = (hash << 1) | (thumbnail_row[x] < thumbnail_row[x + 1]) as u64;
+		}
+	}
+
+	hash
+}
+
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use std::path::PathBuf;
+
+	use crate::run_test;
+	use crate::read_wrl_file;
+
+	#[test]
+	fn test_fingerprint_map_similarity() {
+		run_test!({
+			// Arrange
+			let wrl_file = read_wrl_file(&PathBuf::from("test_files/reference/REF.WRL")).unwrap();
+			let mut edited_wrl_file = wrl_file.clone();
+			edited_wrl_file.bigmap[0] = 1;
+			let mut reordered_wrl_file = wrl_file.clone();
+			reordered_wrl_file.bigmap.reverse();
+			reordered_wrl_file.minimap.reverse();
+			let wide_wrl_file = read_wrl_file(&PathBuf::from("test_files/reference/REF_WIDE.WRL")).unwrap();
+			let wrl_view = WrlView::open(&PathBuf::from("test_files/reference/REF.WRL")).unwrap();
+
+			// Act
+			let fingerprint = fingerprint_map(&wrl_file);
+			let view_fingerprint = fingerprint_wrl_view(&wrl_view);
+			let edited_fingerprint = fingerprint_map(&edited_wrl_file);
+			let reordered_fingerprint = fingerprint_map(&reordered_wrl_file);
+			let wide_fingerprint = fingerprint_map(&wide_wrl_file);
+
+			// Assert
+			assert_eq!(fingerprint.cell_hashes.len(), 256);
+			assert_eq!(view_fingerprint, fingerprint);
+			assert_eq!(fingerprint.similarity(&fingerprint), 1.0);
+			assert_eq!(edited_fingerprint.cell_similarity(&fingerprint), 255.0 / 256.0);
+			assert_eq!(edited_fingerprint.minimap_similarity(&fingerprint), 1.0);
+			assert_eq!(reordered_fingerprint.cell_similarity(&fingerprint), 0.0);
+			assert!(reordered_fingerprint.similarity(&fingerprint) <= 1.0 - CELL_SIMILARITY_WEIGHT);
+			assert_eq!(wide_fingerprint.cell_similarity(&fingerprint), 0.0);
+
+			let logs = get_captured_logs();
+			assert!(logs.is_empty());
+		});
+	}
+}
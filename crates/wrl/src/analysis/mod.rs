@@ -0,0 +1,8 @@
+pub mod analyze_terrain;
+pub use analyze_terrain::*;
+
+pub mod cluster_similar_maps;
+pub use cluster_similar_maps::*;
+
+pub mod map_fingerprint;
+pub use map_fingerprint::*;
@@ -1,3 +1,12 @@
+/// Magic bytes identifying a WRL file.
+pub const WRL_MAGIC: [u8; 3] = [0x57, 0x52, 0x4C];
+
+/// The only WRL format version used by the game, stored little-endian after the magic.
+pub const WRL_FORMAT_VERSION: u16 = 1;
+
+/// Largest map width and height used by the original game.
+pub const MAX_MAP_SIZE: u16 = 112;
+
 pub const TILE_SIZE: usize = 64;
 
 pub const TILE_DATA_SIZE: usize = TILE_SIZE * TILE_SIZE;
@@ -0,0 +1,206 @@
+use std::fmt;
+use std::io;
+
+use crate::consts::TILE_DATA_SIZE;
+
+/// Sections of the WRL file in the order they are stored on disk.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WrlSection {
+	Header,
+	Width,
+	Height,
+	Minimap,
+	Bigmap,
+	TileCount,
+	Tiles,
+	Palette,
+	PassTable,
+	Tail,
+}
+
+impl WrlSection {
+	/// Returns the section which contains the byte at the given offset.
+	pub fn at_offset(width: u16, height: u16, tile_count: u16, offset: u64) -> WrlSection {
+		let cell_count = width as u64 * height as u64;
+		let sections = [
+			(WrlSection::Header, 5),
+			(WrlSection::Width, 2),
+			(WrlSection::Height, 2),
+			(WrlSection::Minimap, cell_count),
+			(WrlSection::Bigmap, cell_count * 2),
+			(WrlSection::TileCount, 2),
+			(WrlSection::Tiles, tile_count as u64 * TILE_DATA_SIZE as u64),
+			(WrlSection::Palette, 256 * 3),
+			(WrlSection::PassTable, tile_count as u64),
+		];
+
+		let mut section_end = 0u64;
+		for (section, size) in sections {
+			section_end += size;
+			if offset < section_end {
+				return section;
+			}
+		}
+
+		WrlSection::Tail
+	}
+}
+
+impl fmt::Display for WrlSection {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		let name = match self {
+			WrlSection::Header => "header",
+			WrlSection::Width => "width",
+			WrlSection::Height => "height",
+			WrlSection::Minimap => "minimap",
+			WrlSection::Bigmap => "bigmap",
+			WrlSection::TileCount => "tile count",
+			WrlSection::Tiles => "tiles",
+			WrlSection::Palette => "palette",
+			WrlSection::PassTable => "pass table",
+			WrlSection::Tail => "tail header",
+		};
+		write!(f, "{}", name)
+	}
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum WrlError {
+	/// File system or stream error.
+	Io(String),
+	/// The file does not start with the WRL magic bytes.
+	InvalidMagic { found: [u8; 3] },
+	/// The WRL format version is not supported.
+	UnsupportedVersion { version: u16 },
+	/// The file ends before the given section is complete.
+	Truncated { section: WrlSection, expected: u64, found: u64 },
+	/// The in-memory section size does not match the map dimensions.
+	InvalidSectionSize { section: WrlSection, expected: u64, found: u64 },
+	/// Width or height is not usable as a map size.
+	InvalidDimensions { width: u16, height: u16 },
+	/// A bigmap cell references a tile which does not exist.
+	InvalidTileIndex { cell: usize, tile_index: u16, tile_count: u16 },
+	/// A tile passed as an argument does not exist.
+	TileNotFound { tile_index: u16, tile_count: u16 },
+	/// The operation would need more tiles than the WRL format can store.
+	TooManyTiles { tile_count: usize },
+	/// A cell position is outside of the map.
+	CellOutOfBounds { x: u16, y: u16 },
+	/// A pass table entry holds a value which is not a known passability class.
+	InvalidPassability { tile_index: u16, value: u8 },
+	/// A cell rectangle does not fit inside the map.
+	InvalidRegion { x: u16, y: u16, width: u16, height: u16 },
+	/// Data after the WRL content is not a valid tail header.
+	InvalidTail(String),
+	/// The tail header hash_id does not match the WRL content.
+	HashMismatch { expected: String, found: String },
+	/// Failed to serialize data, e.g. the tail header JSON.
+	Serialization(String),
+	/// Failed to create or encode an image.
+	Image(String),
+}
+
+impl fmt::Display for WrlError {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			WrlError::Io(message) => write!(f, "I/O error: {}", message),
+			WrlError::InvalidMagic { found } => write!(
+				f, "Not a WRL file: invalid header magic {:02X?}", found
+			),
+			WrlError::UnsupportedVersion { version } => write!(
+				f, "Unsupported WRL format version: {}", version
+			),
+			WrlError::Truncated { section, expected, found } => write!(
+				f, "File is truncated in {} section: expected {} bytes, found {}", section, expected, found
+			),
+			WrlError::InvalidSectionSize { section, expected, found } => write!(
+				f, "Invalid {} section size: expected {} bytes, found {}", section, expected, found
+			),
+			WrlError::InvalidDimensions { width, height } => write!(
+				f, "Invalid map dimensions: {}x{}", width, height
+			),
+			WrlError::InvalidTileIndex { cell, tile_index, tile_count } => write!(
+				f, "Invalid tile index {} in bigmap cell {}: map has {} tiles", tile_index, cell, tile_count
+			),
+			WrlError::TileNotFound { tile_index, tile_count } => write!(
+				f, "Tile {} not found: map has {} tiles", tile_index, tile_count
+			),
+			WrlError::TooManyTiles { tile_count } => write!(
+				f, "Too many tiles: {} exceeds the limit of {}", tile_count, u16::MAX
+			),
+			WrlError::CellOutOfBounds { x, y } => write!(
+				f, "Cell {},{} is outside of the map", x, y
+			),
+			WrlError::InvalidPassability { tile_index, value } => write!(
+				f, "Invalid pass table value {} for tile {}", value, tile_index
+			),
+			WrlError::InvalidRegion { x, y, width, height } => write!(
+				f, "Region {}x{} at {},{} does not fit inside the map", width, height, x, y
+			),
+			WrlError::InvalidTail(message) => write!(f, "Invalid tail header: {}", message),
+			WrlError::HashMismatch { expected, found } => write!(
+				f, "Tail header hash_id mismatch: expected {}, found {}", expected, found
+			),
+			WrlError::Serialization(message) => write!(f, "Serialization error: {}", message),
+			WrlError::Image(message) => write!(f, "Image error: {}", message),
+		}
+	}
+}
+
+impl std::error::Error for WrlError {}
+
+impl From<io::Error> for WrlError {
+	fn from(e: io::Error) -> Self {
+		WrlError::Io(e.to_string())
+	}
+}
+
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	use crate::run_test;
+
+	#[test]
+	fn test_wrl_section_at_offset() {
+		run_test!({
+			// Arrange
+			let width = 16;
+			let height = 16;
+			let tile_count = 64;
+
+			// Act & Assert
+			assert_eq!(WrlSection::at_offset(width, height, tile_count, 0), WrlSection::Header);
+			assert_eq!(WrlSection::at_offset(width, height, tile_count, 5), WrlSection::Width);
+			assert_eq!(WrlSection::at_offset(width, height, tile_count, 8), WrlSection::Height);
+			assert_eq!(WrlSection::at_offset(width, height, tile_count, 9), WrlSection::Minimap);
+			assert_eq!(WrlSection::at_offset(width, height, tile_count, 265), WrlSection::Bigmap);
+			assert_eq!(WrlSection::at_offset(width, height, tile_count, 777), WrlSection::TileCount);
+			assert_eq!(WrlSection::at_offset(width, height, tile_count, 779), WrlSection::Tiles);
+			assert_eq!(WrlSection::at_offset(width, height, tile_count, 262923), WrlSection::Palette);
+			assert_eq!(WrlSection::at_offset(width, height, tile_count, 263691), WrlSection::PassTable);
+			assert_eq!(WrlSection::at_offset(width, height, tile_count, 263755), WrlSection::Tail);
+
+			let logs = get_captured_logs();
+			assert!(logs.is_empty());
+		});
+	}
+
+	#[test]
+	fn test_wrl_error_display() {
+		run_test!({
+			// Arrange
+			let error = WrlError::Truncated { section: WrlSection::PassTable, expected: 64, found: 63 };
+
+			// Act
+			let message = error.to_string();
+
+			// Assert
+			assert_eq!(message, "File is truncated in pass table section: expected 64 bytes, found 63");
+
+			let logs = get_captured_logs();
+			assert!(logs.is_empty());
+		});
+	}
+}
@@ -2,6 +2,7 @@ use std::fs::File;
 use std::io::{Read, Write};
 use std::path::Path;
 
+use crate::error::{WrlError, WrlSection};
 use crate::file::calc_expected_file_size::calc_expected_file_size;
 use crate::file::get_file_size::get_file_size;
 use crate::file::read_wrl_header::read_wrl_header;
@@ -9,22 +10,22 @@ use crate::file::read_wrl_header::read_wrl_header;
 pub fn copy_wrl_file_without_tail(
 	src: &Path,
 	dest: &Path,
-) -> Result<(), ()> {
+) -> Result<(), WrlError> {
 	if !src.exists() {
 		log::error!("Source file does not exist: {}", src.display());
-		return Err(());
+		return Err(WrlError::Io(format!("Source file does not exist: {}", src.display())));
 	}
 
 	if dest.exists() {
 		log::warn!("Destination file already exists: {}", dest.display());
-		return Err(());
+		return Err(WrlError::Io(format!("Destination file already exists: {}", dest.display())));
 	}
 
 	let wrl_header = match read_wrl_header(src) {
 		Ok(header) => header,
-		Err(_) => {
+		Err(e) => {
 			log::error!("Failed to read WRL header from source file: {}", src.display());
-			return Err(());
+			return Err(e);
 		}
 	};
 
@@ -36,18 +37,22 @@ pub fn copy_wrl_file_without_tail(
 
 	let file_size = match get_file_size(src) {
 		Ok(size) => size,
-		Err(_) => {
+		Err(e) => {
 			log::error!("Failed to get file size for source file: {}", src.display());
-			return Err(());
+			return Err(e);
 		}
 	};
 
 	if file_size < wrl_data_length {
 		log::error!("Source file size is smaller than expected WRL data size: {}", src.display());
-		return Err(());
+		return Err(WrlError::Truncated {
+			section: WrlSection::at_offset(wrl_header.width, wrl_header.height, wrl_header.tile_count, file_size),
+			expected: wrl_data_length,
+			found: file_size,
+		});
 	}
 
-	if let Err(_) = copy_file_up_to_length(src, dest, wrl_data_length as u64) {
+	if let Err(e) = copy_file_up_to_length(src, dest, wrl_data_length) {
 		log::error!("Failed to copy WRL file from {} to {}. Reverting changes.", src.display(), dest.display());
 
 		if let Err(e) = std::fs::remove_file(dest) {
@@ -56,7 +61,7 @@ pub fn copy_wrl_file_without_tail(
 			log::info!("Destination file: {} was not removed. Please remove it manually.", dest.display());
 		}
 
-		return Err(());
+		return Err(e);
 	}
 
 	Ok(())
@@ -67,12 +72,12 @@ fn copy_file_up_to_length(
     src_path: &Path,
     dest_path: &Path,
     length: u64,
-) -> Result<(), ()> {
+) -> Result<(), WrlError> {
     let mut src_file = match File::open(src_path) {
 		Ok(file) => file,
 		Err(e) => {
 			log::error!("Failed to open source file: {}", e);
-			return Err(());
+			return Err(e.into());
 		}
 	};
 
@@ -80,7 +85,7 @@ fn copy_file_up_to_length(
 		Ok(file) => file,
 		Err(e) => {
 			log::error!("Failed to create destination file: {}", e);
-			return Err(());
+			return Err(e.into());
 		}
 	};
 
@@ -98,7 +103,7 @@ fn copy_file_up_to_length(
             Ok(bytes) => bytes,
             Err(e) => {
                 log::error!("Failed to read from source file: {}", e);
-                return Err(());
+                return Err(e.into());
             }
         };
 
@@ -108,7 +113,7 @@ fn copy_file_up_to_length(
 
         if let Err(e) = dest_file.write_all(&buffer[..bytes_read]) {
             log::error!("Failed to write to destination file: {}", e);
-            return Err(());
+            return Err(e.into());
         }
 
         bytes_copied += bytes_read as u64;
@@ -120,7 +125,7 @@ fn copy_file_up_to_length(
 
     if let Err(e) = dest_file.flush() {
         log::error!("Failed to flush destination file: {}", e);
-        return Err(());
+        return Err(e.into());
     }
 
     Ok(())
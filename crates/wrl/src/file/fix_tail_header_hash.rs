@@ -0,0 +1,74 @@
+use std::path::Path;
+
+use crate::error::WrlError;
+use crate::hash_wrl_file_without_tail::hash_wrl_file_without_tail;
+use crate::read_wrl_tail_header;
+use crate::write_wrl_tail_header;
+
+/// Rewrites the tail header with the `hash_id` of the current WRL content.
+/// All other tail header fields are kept.
+/// Returns `true` when the tail header was stale and has been rewritten.
+pub fn fix_tail_header_hash(file_path: &Path) -> Result<bool, WrlError> {
+	let mut tail_header = match read_wrl_tail_header(file_path)? {
+		Some(tail_header) => tail_header,
+		None => return Ok(false),
+	};
+
+	let hash_id = hash_wrl_file_without_tail(file_path)?;
+
+	if tail_header.hash_id == hash_id {
+		return Ok(false);
+	}
+
+	log::info!("Updating stale tail header hash_id of file: {}", file_path.display());
+	tail_header.hash_id = hash_id;
+	write_wrl_tail_header(file_path, &tail_header)?;
+
+	Ok(true)
+}
+
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use std::path::PathBuf;
+
+	use crate::run_test;
+	use crate::verify_tail_header;
+
+	#[test]
+	fn test_fix_tail_header_hash() {
+		run_test!({
+			// Arrange
+			std::fs::create_dir_all("test_files/temp").expect("Failed to create temp directory");
+			let source_file_path = PathBuf::from("test_files/reference/REF.WRL.TAIL");
+			let file_path = PathBuf::from("test_files/temp/REF.WRL.TAIL.FIX");
+			if let Err(e) = std::fs::copy(&source_file_path, &file_path) {
+				log::error!("TEST INTERRUPTED! Failed to copy {} to {}: {}", source_file_path.display(), file_path.display(), e);
+				return;
+			}
+
+			// Act
+			let fixed = fix_tail_header_hash(&file_path).unwrap();
+			let fixed_again = fix_tail_header_hash(&file_path).unwrap();
+
+			// Assert
+			assert!(fixed);
+			assert!(!fixed_again);
+			assert!(verify_tail_header(&file_path).is_ok());
+
+			let tail_header = read_wrl_tail_header(&file_path).unwrap().unwrap();
+			assert_eq!(tail_header.hash_id, "93bcb82dc19b0d7a9a6eb9faf54869c9d3e353e0fdf4a847096ceff8720614aa");
+			assert_eq!(tail_header.name, "custom map name");
+
+			// Clean up
+			if let Err(e) = std::fs::remove_file(&file_path) {
+				log::error!("Failed to remove test file after test: {}", file_path.display());
+				log::error!("{}", e);
+			}
+
+			let logs = get_captured_logs();
+			assert_eq!(logs, vec!["[INFO] Updating stale tail header hash_id of file: test_files/temp/REF.WRL.TAIL.FIX"]);
+		});
+	}
+}
@@ -1,20 +1,22 @@
 use std::fs::metadata;
 use std::path::Path;
 
+use crate::error::WrlError;
+
 /// Gets the size of the file at the specified path.
-pub fn get_file_size(file_path: &Path) -> Result<u64, ()> {
+pub fn get_file_size(file_path: &Path) -> Result<u64, WrlError> {
 	match metadata(file_path) {
 		Ok(meta) => {
 			if meta.is_file() {
 				Ok(meta.len())
 			} else {
 				log::error!("Expected a file: {}", file_path.display());
-				Err(())
+				Err(WrlError::Io(format!("Expected a file: {}", file_path.display())))
 			}
 		}
-		Err(_) => {
+		Err(e) => {
 			log::error!("Failed to get file metadata: {}", file_path.display());
-			return Err(());
+			Err(e.into())
 		},
 	}
 }
@@ -1,86 +1,40 @@
 use blake3::{Hasher};
-use memmap2::Mmap;
-use std::fs::File;
 use std::path::Path;
 
 use crate::calc_expected_file_size::calc_expected_file_size;
-use crate::get_file_size::get_file_size;
-use crate::read_wrl_header::read_wrl_header;
+use crate::error::{WrlError, WrlSection};
+use crate::file::wrl_view::WrlView;
 
 
-pub fn hash_wrl_file_without_tail(file_path: &Path) -> Result<String, ()> {
-	let wrl_header = match read_wrl_header(file_path) {
-		Ok(header) => header,
-		Err(_) => {
+/// Hashes the WRL content of the file, ignoring a tail header.
+/// The file is memory-mapped and hashed in parallel.
+pub fn hash_wrl_file_without_tail(file_path: &Path) -> Result<String, WrlError> {
+	let wrl_view = match WrlView::open(file_path) {
+		Ok(view) => view,
+		Err(e) => {
 			log::error!("Failed to read WRL header from file: {}", file_path.display());
-			return Err(());
+			return Err(e);
 		}
 	};
 
-    let expected_file_size = calc_expected_file_size(
-		wrl_header.width,
-		wrl_header.height,
-		wrl_header.tile_count,
+	let expected_file_size = calc_expected_file_size(
+		wrl_view.width(),
+		wrl_view.height(),
+		wrl_view.tile_count(),
 	);
-
-	let file_size = match get_file_size(file_path) {
-		Ok(size) => size,
-		Err(_) => {
-			log::error!("Failed to get file size for: {}", file_path.display());
-			return Err(());
-		}
-	};
+	let file_size = wrl_view.file_size();
 
 	if expected_file_size > file_size {
 		log::error!("File size is smaller than expected WRL data size: {}", file_path.display());
-		return Err(());
-	}
-
-	if expected_file_size == file_size {
-		return hash_full_file(file_path);
-	}
-
-	hash_part_of_file(file_path, expected_file_size)
-}
-
-
-fn hash_full_file(path: &Path) -> Result<String, ()> {
-	let mut hasher = blake3::Hasher::new();
-
-	if let Err(_) = hasher.update_mmap_rayon(path) {
-		log::error!("Failed to hash file: {}", path.display());
-		return Err(());
+		return Err(WrlError::Truncated {
+			section: WrlSection::at_offset(wrl_view.width(), wrl_view.height(), wrl_view.tile_count(), file_size),
+			expected: expected_file_size,
+			found: file_size,
+		});
 	}
 
-	let hash = hasher.finalize();
-
-	Ok(hash.to_string())
-}
-
-fn hash_part_of_file(file_path: &Path, bytes_to_hash: u64) -> Result<String, ()> {
-	let file = match File::open(file_path) {
-		Ok(file) => file,
-		Err(e) => {
-			log::error!("Failed to open file: {}", file_path.display());
-			log::error!("{}", e);
-			return Err(());
-		}
-	};
-
-	let mmap = unsafe {
-		match Mmap::map(&file) {
-			Ok(mmap) => mmap,
-			Err(_) => {
-				log::error!("Failed to map file: {}", file_path.display());
-				return Err(());
-			}
-		}
-	};
-
-	let data_to_hash = &mmap[..(bytes_to_hash as usize)];
-    let mut hasher = Hasher::new();
-
-    hasher.update_rayon(data_to_hash);
+	let mut hasher = Hasher::new();
+	hasher.update_rayon(wrl_view.content()?);
 
 	let hash = hasher.finalize();
 
@@ -140,7 +94,11 @@ mod tests {
 			let hash = hash_wrl_file_without_tail(&test_file_path);
 
 			// Assert
-			assert!(hash.is_err());
+			assert_eq!(hash.unwrap_err(), WrlError::Truncated {
+				section: WrlSection::PassTable,
+				expected: 1050379,
+				found: 1050378,
+			});
 
 			let logs = get_captured_logs();
 			assert_eq!(logs.len(), 1);
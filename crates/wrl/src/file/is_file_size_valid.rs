@@ -1,17 +1,18 @@
-use byteorder::{LittleEndian, ReadBytesExt};
 use std::fs::File;
 use std::io::{Seek, SeekFrom};
 use std::path::Path;
 
+use crate::error::{WrlError, WrlSection};
 use crate::file::calc_expected_file_size::calc_expected_file_size;
+use crate::file::read_section::read_section_u16;
 
 /// Checks if file size is at least valid WRL data size.
-pub fn is_file_size_valid(file_path: &Path) -> Result<bool, ()> {
+pub fn is_file_size_valid(file_path: &Path) -> Result<bool, WrlError> {
 	let mut file = match File::open(file_path) {
 		Ok(file) => file,
-		Err(_) => {
+		Err(e) => {
 			log::error!("Failed to open file: {}", file_path.display());
-			return Err(());
+			return Err(e.into());
 		}
 	};
 
@@ -19,49 +20,37 @@ pub fn is_file_size_valid(file_path: &Path) -> Result<bool, ()> {
 		Ok(_) => {},
 		Err(e) => {
 			log::error!("Failed to skip header in file: {}", e);
-			return Err(());
+			return Err(e.into());
 		}
 	}
 
-	let width = match file.read_u16::<LittleEndian>() {
-		Ok(w) => w,
-		Err(e) => {
-			log::error!("Failed to read width from file: {}", e);
-			return Err(());
-		}
-	};
+	let width = read_section_u16(&mut file, WrlSection::Width).inspect_err(|e| {
+		log::error!("Failed to read width from file: {}", e);
+	})?;
 
-	let height = match file.read_u16::<LittleEndian>() {
-		Ok(h) => h,
-		Err(e) => {
-			log::error!("Failed to read height from file: {}", e);
-			return Err(());
-		}
-	};
+	let height = read_section_u16(&mut file, WrlSection::Height).inspect_err(|e| {
+		log::error!("Failed to read height from file: {}", e);
+	})?;
 
-	match file.seek(SeekFrom::Current((width * height) as i64)) {
+	match file.seek(SeekFrom::Current(width as i64 * height as i64)) {
 		Ok(_) => {},
 		Err(e) => {
 			log::error!("Failed to skip minimap in file: {}", e);
-			return Err(());
+			return Err(e.into());
 		}
 	};
 
-	match file.seek(SeekFrom::Current((width * height * 2) as i64)) {
+	match file.seek(SeekFrom::Current(width as i64 * height as i64 * 2)) {
 		Ok(_) => {},
 		Err(e) => {
 			log::error!("Failed to skip bigmap in file: {}", e);
-			return Err(());
+			return Err(e.into());
 		}
 	};
 
-	let tile_count = match file.read_u16::<LittleEndian>() {
-		Ok(tc) => tc,
-		Err(e) => {
-			log::error!("Failed to read tile count from file: {}", e);
-			return Err(());
-		}
-	};
+	let tile_count = read_section_u16(&mut file, WrlSection::TileCount).inspect_err(|e| {
+		log::error!("Failed to read tile count from file: {}", e);
+	})?;
 
 	let expected_length = calc_expected_file_size(width, height, tile_count);
 
@@ -76,7 +65,7 @@ pub fn is_file_size_valid(file_path: &Path) -> Result<bool, ()> {
 		}
 		Err(e) => {
 			log::error!("Failed to get file metadata: {}", e);
-			Err(())
+			Err(e.into())
 		}
 	}
 }
@@ -0,0 +1,273 @@
+use std::fmt;
+use std::path::Path;
+
+use serde::Serialize;
+
+use crate::consts::MAX_MAP_SIZE;
+use crate::error::WrlError;
+use crate::file::calc_expected_file_size::calc_expected_file_size;
+use crate::ops::{compare_minimap, MINIMAP_COLOR_TOLERANCE};
+use crate::passability::Passability;
+use crate::structs::{WRLFile, WRLTailHeader};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LintSeverity {
+	Info,
+	Warning,
+	Error,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct LintFinding {
+	pub severity: LintSeverity,
+	/// Stable identifier of the check, e.g. `invalid_tile_index`.
+	pub code: String,
+	pub message: String,
+}
+
+impl fmt::Display for LintFinding {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		let severity = match self.severity {
+			LintSeverity::Info => "info",
+			LintSeverity::Warning => "warning",
+			LintSeverity::Error => "error",
+		};
+		write!(f, "[{}] {}: {}", severity, self.code, self.message)
+	}
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct LintReport {
+	pub findings: Vec<LintFinding>,
+}
+
+impl LintReport {
+	pub fn has_errors(&self) -> bool {
+		self.findings.iter().any(|finding| finding.severity == LintSeverity::Error)
+	}
+
+	pub fn count(&self, severity: LintSeverity) -> usize {
+		self.findings.iter().filter(|finding| finding.severity == severity).count()
+	}
+
+	fn push(&mut self, severity: LintSeverity, code: &str, message: String) {
+		self.findings.push(LintFinding {
+			severity,
+			code: code.to_string(),
+			message,
+		});
+	}
+}
+
+/// Runs all lint checks on the content of a WRL file.
+/// Unlike `verify_file`, a lint does not stop at the first problem
+/// unless the data cannot be parsed at all.
+pub fn lint_wrl_bytes(bytes: &[u8]) -> LintReport {
+	let mut report = LintReport::default();
+
+	let wrl_file = match WRLFile::from_bytes(bytes) {
+		Ok(wrl_file) => wrl_file,
+		Err(WrlError::InvalidDimensions { width, height }) => {
+			report.push(LintSeverity::Error, "zero_size", format!("Map has zero size: {}x{}", width, height));
+			return report;
+		}
+		Err(e) => {
+			report.push(LintSeverity::Error, "unreadable", e.to_string());
+			return report;
+		}
+	};
+
+	if wrl_file.width > MAX_MAP_SIZE || wrl_file.height > MAX_MAP_SIZE {
+		report.push(LintSeverity::Error, "map_too_large", format!(
+			"Map size {}x{} exceeds the maximum of {}x{} supported by the game",
+			wrl_file.width, wrl_file.height, MAX_MAP_SIZE, MAX_MAP_SIZE
+		));
+	}
+
+	lint_tile_indices(&wrl_file, &mut report);
+	lint_pass_table(&wrl_file, &mut report);
+	lint_minimap(&wrl_file, &mut report);
+
+	let data_length = calc_expected_file_size(wrl_file.width, wrl_file.height, wrl_file.tile_count) as usize;
+	if bytes.len() > data_length
+		&& let Err(WrlError::InvalidTail(message)) = WRLTailHeader::from_json(&String::from_utf8_lossy(&bytes[data_length..]))
+	{
+		report.push(LintSeverity::Warning, "invalid_tail", format!(
+			"{} bytes after the map data are not a valid tail header: {}",
+			bytes.len() - data_length, message
+		));
+	}
+
+	report
+}
+
+/// Reads the file and runs all lint checks, see `lint_wrl_bytes`.
+pub fn lint_wrl_file(file_path: &Path) -> Result<LintReport, WrlError> {
+	match std::fs::read(file_path) {
+		Ok(bytes) => Ok(lint_wrl_bytes(&bytes)),
+		Err(e) => {
+			log::error!("Failed to read file: {}", file_path.display());
+			log::error!("{}", e);
+			Err(e.into())
+		}
+	}
+}
+
+fn lint_tile_indices(wrl_file: &WRLFile, report: &mut LintReport) {
+	let invalid_cells: Vec<usize> = wrl_file
+		.bigmap
+		.iter()
+		.enumerate()
+		.filter(|&(_, &tile_index)| tile_index >= wrl_file.tile_count)
+		.map(|(cell, _)| cell)
+		.collect();
+
+	if let Some(&first_cell) = invalid_cells.first() {
+		report.push(LintSeverity::Error, "invalid_tile_index", format!(
+			"{} bigmap cells reference tiles beyond the tile count of {}, first at cell {},{}",
+			invalid_cells.len(),
+			wrl_file.tile_count,
+			first_cell % wrl_file.width as usize,
+			first_cell / wrl_file.width as usize,
+		));
+	}
+}
+
+/// Out of range pass table values are errors for tiles used by the bigmap.
+/// Unused tiles never reach the game, so their values are only warned about.
+fn lint_pass_table(wrl_file: &WRLFile, report: &mut LintReport) {
+	let mut is_used = vec![false; wrl_file.pass_table.len()];
+	for &tile_index in &wrl_file.bigmap {
+		if let Some(used) = is_used.get_mut(tile_index as usize) {
+			*used = true;
+		}
+	}
+
+	let (used_tiles, unused_tiles): (Vec<usize>, Vec<usize>) = wrl_file
+		.pass_table
+		.iter()
+		.enumerate()
+		.filter(|&(_, &value)| Passability::from_u8(value).is_none())
+		.map(|(tile_index, _)| tile_index)
+		.partition(|&tile_index| is_used[tile_index]);
+
+	if let Some(&first_tile) = used_tiles.first() {
+		report.push(LintSeverity::Error, "invalid_pass_value", format!(
+			"{} pass table entries of used tiles are out of range, first at tile {} with value {}",
+			used_tiles.len(),
+			first_tile,
+			wrl_file.pass_table[first_tile],
+		));
+	}
+
+	if let Some(&first_tile) = unused_tiles.first() {
+		report.push(LintSeverity::Warning, "unused_invalid_pass_value", format!(
+			"{} pass table entries of unused tiles are out of range, first at tile {} with value {}",
+			unused_tiles.len(),
+			first_tile,
+			wrl_file.pass_table[first_tile],
+		));
+	}
+}
+
+fn lint_minimap(wrl_file: &WRLFile, report: &mut LintReport) {
+	// The minimap can only be computed when all tile indices are valid.
+	let Ok(minimap_report) = compare_minimap(wrl_file) else {
+		return;
+	};
+
+	if !minimap_report.is_within_tolerance() {
+		report.push(LintSeverity::Warning, "minimap_mismatch", format!(
+			"{} of {} minimap cells differ from the bigmap colors by more than {}",
+			minimap_report.cells_beyond_tolerance, minimap_report.cell_count, MINIMAP_COLOR_TOLERANCE
+		));
+	}
+}
+
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use std::path::PathBuf;
+
+	use crate::run_test;
+
+	fn finding_codes(report: &LintReport) -> Vec<&str> {
+		report.findings.iter().map(|finding| finding.code.as_str()).collect()
+	}
+
+	#[test]
+	fn test_lint_wrl_file() {
+		run_test!({
+			// Arrange
+			let path = PathBuf::from("test_files/reference/REF.WRL.TAIL");
+
+			// Act
+			let report = lint_wrl_file(&path).unwrap();
+
+			// Assert
+			// The reference minimap is synthetic, most cells do not show their tile color.
+			assert_eq!(finding_codes(&report), vec!["minimap_mismatch"]);
+			assert!(!report.has_errors());
+			assert_eq!(report.count(LintSeverity::Warning), 1);
+			assert_eq!(report.findings[0].message, "180 of 256 minimap cells differ from the bigmap colors by more than 16");
+
+			let logs = get_captured_logs();
+			assert!(logs.is_empty());
+		});
+	}
+
+	#[test]
+	fn test_lint_wrl_bytes_errors() {
+		run_test!({
+			// Arrange
+			let mut wrl_file = WRLFile::from_bytes(&std::fs::read("test_files/reference/REF.WRL").unwrap()).unwrap();
+			wrl_file.bigmap[17] = 64;
+			wrl_file.bigmap[18] = 100;
+			// Tile 4 is used by cell 2, tile 3 is not used.
+			wrl_file.pass_table[3] = 7;
+			wrl_file.pass_table[4] = 8;
+			let mut bytes = wrl_file.to_bytes();
+			bytes.extend_from_slice(b"garbage");
+
+			// Act
+			let report = lint_wrl_bytes(&bytes);
+
+			// Assert
+			assert_eq!(
+				finding_codes(&report),
+				vec!["invalid_tile_index", "invalid_pass_value", "unused_invalid_pass_value", "invalid_tail"]
+			);
+			assert!(report.has_errors());
+			assert_eq!(report.findings[0].message, "2 bigmap cells reference tiles beyond the tile count of 64, first at cell 1,1");
+			assert_eq!(report.findings[1].to_string(), "[error] invalid_pass_value: 1 pass table entries of used tiles are out of range, first at tile 4 with value 8");
+			assert_eq!(report.findings[2].severity, LintSeverity::Warning);
+			assert_eq!(report.findings[2].message, "1 pass table entries of unused tiles are out of range, first at tile 3 with value 7");
+
+			let logs = get_captured_logs();
+			assert!(logs.is_empty());
+		});
+	}
+
+	#[test]
+	fn test_lint_wrl_bytes_unreadable() {
+		run_test!({
+			// Arrange
+			let zero_size = [0x57, 0x52, 0x4c, 0x01, 0x00, 0x00, 0x00, 0x10, 0x00];
+			let truncated = &std::fs::read("test_files/reference/REF.WRL.TRIMMED").unwrap();
+
+			// Act
+			let zero_size_report = lint_wrl_bytes(&zero_size);
+			let truncated_report = lint_wrl_bytes(truncated);
+
+			// Assert
+			assert_eq!(finding_codes(&zero_size_report), vec!["zero_size"]);
+			assert_eq!(finding_codes(&truncated_report), vec!["unreadable"]);
+			assert_eq!(truncated_report.findings[0].message, "File is truncated in pass table section: expected 256 bytes, found 255");
+
+			let logs = get_captured_logs();
+			assert!(logs.is_empty());
+		});
+	}
+}
@@ -4,6 +4,9 @@ pub use calc_expected_file_size::*;
 pub mod copy_wrl_file_without_tail;
 pub use copy_wrl_file_without_tail::*;
 
+pub mod fix_tail_header_hash;
+pub use fix_tail_header_hash::*;
+
 pub mod get_file_size;
 pub use get_file_size::*;
 
@@ -13,6 +16,14 @@ pub use hash_wrl_file_without_tail::*;
 pub mod is_file_size_valid;
 pub use is_file_size_valid::*;
 
+pub mod lint_wrl_file;
+pub use lint_wrl_file::*;
+
+pub mod read_palette_file;
+pub use read_palette_file::*;
+
+mod read_section;
+
 pub mod read_wrl_header;
 pub use read_wrl_header::*;
 
@@ -28,8 +39,14 @@ pub mod verify_file;
 pub mod verify_file_content;
 pub use verify_file_content::*;
 
-pub use verify_tail_header::*;
 pub mod verify_tail_header;
+pub use verify_tail_header::*;
 
 pub mod write_wrl_tail_header;
 pub use write_wrl_tail_header::*;
+
+pub mod write_wrl_file;
+pub use write_wrl_file::*;
+
+pub mod wrl_view;
+pub use wrl_view::*;
@@ -0,0 +1,82 @@
+use std::fs;
+use std::path::Path;
+
+use crate::error::WrlError;
+use crate::file::get_file_size::get_file_size;
+use crate::file::read_wrl_header::read_wrl_header;
+
+/// Size of a raw palette file, 256 RGB colors as stored in the WRL palette section.
+pub const PALETTE_FILE_SIZE: u64 = 256 * 3;
+
+/// Reads a 256-color palette from a raw palette file or from the palette section of a WRL file.
+/// Files of exactly `PALETTE_FILE_SIZE` bytes are read as raw palettes, anything else as a WRL file.
+pub fn read_palette_file(file_path: &Path) -> Result<Vec<u8>, WrlError> {
+	if get_file_size(file_path)? != PALETTE_FILE_SIZE {
+		return read_wrl_header(file_path).map(|header| header.palette);
+	}
+
+	match fs::read(file_path) {
+		Ok(palette) => Ok(palette),
+		Err(e) => {
+			log::error!("Failed to read palette file: {}", file_path.display());
+			Err(e.into())
+		}
+	}
+}
+
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use std::path::PathBuf;
+
+	use crate::run_test;
+	use crate::read_wrl_file;
+
+	#[test]
+	fn test_read_palette_file() {
+		run_test!({
+			// Arrange
+			let wrl_file = read_wrl_file(&PathBuf::from("test_files/reference/REF.WRL")).unwrap();
+			std::fs::create_dir_all("test_files/temp").expect("Failed to create temp directory");
+			let raw_palette_path = PathBuf::from("test_files/temp/REF.PAL");
+			let raw_palette: Vec<u8> = (0..256).flat_map(|i| [(i / 4) as u8, 0, 63]).collect();
+			std::fs::write(&raw_palette_path, &raw_palette).unwrap();
+
+			// Act
+			let wrl_palette = read_palette_file(&PathBuf::from("test_files/reference/REF.WRL")).unwrap();
+			let read_raw_palette = read_palette_file(&raw_palette_path).unwrap();
+
+			// Assert
+			assert_eq!(wrl_palette, wrl_file.palette);
+			assert_eq!(read_raw_palette, raw_palette);
+
+			// Clean up
+			if let Err(e) = std::fs::remove_file(&raw_palette_path) {
+				log::error!("Failed to remove test file after test: {}", raw_palette_path.display());
+				log::error!("{}", e);
+			}
+
+			let logs = get_captured_logs();
+			assert!(logs.is_empty());
+		});
+	}
+
+	#[test]
+	fn test_read_palette_file_nonexistent_file() {
+		run_test!({
+			// Arrange
+			let test_file_path = PathBuf::from("test_files/reference/NON_EXISTENT_FILE.PAL");
+
+			// Act
+			let result = read_palette_file(&test_file_path);
+
+			// Assert
+			assert!(result.is_err());
+
+			let logs = get_captured_logs();
+			assert_eq!(logs.len(), 1);
+			assert_eq!(logs[0], "[ERROR] Failed to get file metadata: test_files/reference/NON_EXISTENT_FILE.PAL");
+		});
+	}
+}
@@ -0,0 +1,83 @@
+use std::io::{Read, Seek, SeekFrom};
+use std::path::Path;
+
+use crate::error::{WrlError, WrlSection};
+
+/// Reads exactly `size` bytes of the given section.
+pub(crate) fn read_section<R: Read>(
+	reader: &mut R,
+	section: WrlSection,
+	size: usize,
+) -> Result<Vec<u8>, WrlError> {
+	let mut buffer = Vec::new();
+	reader.take(size as u64).read_to_end(&mut buffer)?;
+
+	if buffer.len() < size {
+		return Err(WrlError::Truncated {
+			section,
+			expected: size as u64,
+			found: buffer.len() as u64,
+		});
+	}
+
+	Ok(buffer)
+}
+
+/// Reads a little-endian u16 value of the given section.
+pub(crate) fn read_section_u16<R: Read>(
+	reader: &mut R,
+	section: WrlSection,
+) -> Result<u16, WrlError> {
+	let bytes = read_section(reader, section, 2)?;
+
+	Ok(u16::from_le_bytes([bytes[0], bytes[1]]))
+}
+
+/// Reads `count` little-endian u16 values of the given section.
+pub(crate) fn read_section_u16_vec<R: Read>(
+	reader: &mut R,
+	section: WrlSection,
+	count: usize,
+) -> Result<Vec<u16>, WrlError> {
+	let bytes = read_section(reader, section, count * 2)?;
+
+	Ok(bytes
+		.chunks_exact(2)
+		.map(|pair| u16::from_le_bytes([pair[0], pair[1]]))
+		.collect())
+}
+
+/// Skips `size` bytes of the given section, failing if the stream ends before that.
+pub(crate) fn skip_section<R: Read + Seek>(
+	reader: &mut R,
+	section: WrlSection,
+	size: u64,
+) -> Result<(), WrlError> {
+	let position = reader.stream_position()?;
+	let stream_end = reader.seek(SeekFrom::End(0))?;
+	let available = stream_end.saturating_sub(position);
+
+	if available < size {
+		return Err(WrlError::Truncated {
+			section,
+			expected: size,
+			found: available,
+		});
+	}
+
+	reader.seek(SeekFrom::Start(position + size))?;
+
+	Ok(())
+}
+
+/// Logs a parse error of the given file, naming the broken section when known.
+pub(crate) fn log_read_error(file_path: &Path, e: &WrlError) {
+	match e {
+		WrlError::Truncated { section, .. } => {
+			log::error!("Failed to read {} from file: {}", section, file_path.display());
+		}
+		_ => {
+			log::error!("Failed to read WRL file: {}: {}", file_path.display(), e);
+		}
+	}
+}
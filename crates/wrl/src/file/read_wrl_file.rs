@@ -1,117 +1,73 @@
-use byteorder::{LittleEndian, ReadBytesExt};
-use std::fs::{File};
-use std::io::{Read};
+use std::fs::File;
+use std::io::{Cursor, Read, Seek};
 use std::path::Path;
 
 use crate::consts::TILE_DATA_SIZE;
-use crate::structs::{WRLFile};
+use crate::error::{WrlError, WrlSection};
+use crate::file::read_section::{log_read_error, read_section, read_section_u16, read_section_u16_vec};
+use crate::structs::{WRLFile, WRLFileHeader};
 
 
-/// Reads the WRL file and returns a WRLFile struct.
-pub fn read_wrl_file(file_path: &Path) -> Result<WRLFile, ()> {
-	let mut file = match File::open(file_path) {
-		Ok(file) => file,
-		Err(_) => {
-			log::error!("Failed to open file: {}", file_path.display());
-			return Err(());
-		}
-	};
+impl WRLFile {
+	/// Parses the WRL data starting at the current position of the reader.
+	/// Data after the pass table (e.g. a tail header) is left unread.
+	pub fn from_reader<R: Read + Seek>(reader: &mut R) -> Result<WRLFile, WrlError> {
+		let header = WRLFileHeader::from_bytes(&read_section(reader, WrlSection::Header, WRLFileHeader::SIZE)?)?;
 
-	let header_size = 5;
-	let mut header = vec![0; header_size];
-	match file.read_exact(&mut header) {
-		Ok(_) => {},
-		Err(e) => {
-			log::error!("Failed to read WRL header: {}", e);
-			return Err(());
-		}
-	}
+		let width = read_section_u16(reader, WrlSection::Width)?;
+		let height = read_section_u16(reader, WrlSection::Height)?;
 
-	let width = match file.read_u16::<LittleEndian>() {
-		Ok(w) => w,
-		Err(_) => {
-			log::error!("Failed to read width from file: {}", file_path.display());
-			return Err(());
+		if width == 0 || height == 0 {
+			return Err(WrlError::InvalidDimensions { width, height });
 		}
-	};
 
-	let height = match file.read_u16::<LittleEndian>() {
-		Ok(h) => h,
-		Err(_) => {
-			log::error!("Failed to read height from file: {}", file_path.display());
-			return Err(());
-		}
-	};
+		let minimap_size = (width as usize) * (height as usize);
+		let minimap = read_section(reader, WrlSection::Minimap, minimap_size)?;
 
-	let minimap_size = (width as usize) * (height as usize);
-	let mut minimap = vec![0; minimap_size];
-	match file.read_exact(&mut minimap) {
-		Ok(_) => {},
-		Err(e) => {
-			log::error!("Failed to read minimap from file: {}", e);
-			return Err(());
-		}
-	};
+		let bigmap_size = (width as usize) * (height as usize);
+		let bigmap = read_section_u16_vec(reader, WrlSection::Bigmap, bigmap_size)?;
 
-	let bigmap_size = (width as usize) * (height as usize);
-	let mut bigmap: Vec<u16> = vec![0; bigmap_size];
-	match file.read_u16_into::<LittleEndian>(&mut bigmap) {
-		Ok(_) => {},
-		Err(e) => {
-			log::error!("Failed to read bigmap from file: {}", e);
-			return Err(());
-		}
-	};
+		let tile_count = read_section_u16(reader, WrlSection::TileCount)?;
 
-	let tile_count = match file.read_u16::<LittleEndian>() {
-		Ok(tc) => tc,
-		Err(_) => {
-			log::error!("Failed to read tile count from file: {}", file_path.display());
-			return Err(());
-		}
-	};
+		let tiles_size = tile_count as usize * TILE_DATA_SIZE;
+		let tiles = read_section(reader, WrlSection::Tiles, tiles_size)?;
 
-	let tiles_size = tile_count as usize * TILE_DATA_SIZE;
-	let mut tiles: Vec<u8> = vec![0; tiles_size];
-	match file.read_exact(&mut tiles) {
-		Ok(_) => {},
-		Err(e) => {
-			log::error!("Failed to read tile data from file: {}", e);
-			return Err(());
-		}
-	};
+		let palette_size = 256 * 3;
+		let palette = read_section(reader, WrlSection::Palette, palette_size)?;
 
-	let palette_size = 256 * 3;
-	let mut palette = vec![0; palette_size];
-	match file.read_exact(&mut palette) {
-		Ok(_) => {},
-		Err(e) => {
-			log::error!("Failed to read palette from file: {}", e);
-			return Err(());
-		}
+		let pass_table_size = tile_count as usize;
+		let pass_table = read_section(reader, WrlSection::PassTable, pass_table_size)?;
+
+		Ok(WRLFile {
+			header,
+			width,
+			height,
+			minimap,
+			bigmap,
+			tile_count,
+			tiles,
+			palette,
+			pass_table,
+		})
 	}
 
-	let pass_table_size = tile_count as usize;
-	let mut pass_table = vec![0; pass_table_size];
-	match file.read_exact(&mut pass_table) {
-		Ok(_) => {},
+	/// Parses the WRL data from an in-memory buffer.
+	pub fn from_bytes(bytes: &[u8]) -> Result<WRLFile, WrlError> {
+		WRLFile::from_reader(&mut Cursor::new(bytes))
+	}
+}
+
+/// Reads the WRL file and returns a WRLFile struct.
+pub fn read_wrl_file(file_path: &Path) -> Result<WRLFile, WrlError> {
+	let mut file = match File::open(file_path) {
+		Ok(file) => file,
 		Err(e) => {
-			log::error!("Failed to read pass table from file: {}", e);
-			return Err(());
+			log::error!("Failed to open file: {}", file_path.display());
+			return Err(e.into());
 		}
-	}
+	};
 
-	Ok(WRLFile {
-		header,
-		width,
-		height,
-		minimap,
-		bigmap,
-		tile_count,
-		tiles,
-		palette,
-		pass_table,
-	})
+	WRLFile::from_reader(&mut file).inspect_err(|e| log_read_error(file_path, e))
 }
 
 
@@ -145,6 +101,103 @@ mod tests {
 		});
 	}
 
+	#[test]
+	fn test_wrl_file_from_bytes() {
+		run_test!({
+			// Arrange
+			let bytes = std::fs::read("test_files/reference/REF.WRL.TAIL").unwrap();
+
+			// Act
+			let wrl_file = WRLFile::from_bytes(&bytes).unwrap();
+
+			// Assert
+			assert_eq!(wrl_file.width, 16);
+			assert_eq!(wrl_file.height, 16);
+			assert_eq!(wrl_file.tile_count, 64);
+			assert_eq!(wrl_file.to_bytes(), bytes[..wrl_file.to_bytes().len()]);
+
+			let logs = get_captured_logs();
+			assert!(logs.is_empty());
+		});
+	}
+
+	#[test]
+	fn test_wrl_file_from_reader_at_offset() {
+		run_test!({
+			// Arrange
+			let mut bytes = vec![0xff; 3];
+			bytes.extend(std::fs::read("test_files/reference/REF.WRL").unwrap());
+			let mut reader = Cursor::new(bytes);
+			reader.set_position(3);
+
+			// Act
+			let wrl_file = WRLFile::from_reader(&mut reader).unwrap();
+
+			// Assert
+			assert_eq!(wrl_file.header, WRLFileHeader { magic: *b"WRL", version: 1 });
+			assert_eq!(wrl_file.tile_count, 64);
+			assert_eq!(reader.position(), 3 + 263755);
+
+			let logs = get_captured_logs();
+			assert!(logs.is_empty());
+		});
+	}
+
+	#[test]
+	fn test_wrl_file_from_bytes_invalid_dimensions() {
+		run_test!({
+			// Arrange
+			let bytes = [0x57, 0x52, 0x4c, 0x01, 0x00, 0x00, 0x00, 0x10, 0x00];
+
+			// Act
+			let result = WRLFile::from_bytes(&bytes);
+
+			// Assert
+			assert_eq!(result.unwrap_err(), WrlError::InvalidDimensions { width: 0, height: 16 });
+
+			let logs = get_captured_logs();
+			assert!(logs.is_empty());
+		});
+	}
+
+	#[test]
+	fn test_wrl_file_from_bytes_invalid_magic() {
+		run_test!({
+			// Arrange
+			let mut bytes = std::fs::read("test_files/reference/REF.WRL").unwrap();
+			bytes[0..3].copy_from_slice(b"BMP");
+
+			// Act
+			let result = WRLFile::from_bytes(&bytes);
+
+			// Assert
+			assert_eq!(result.unwrap_err(), WrlError::InvalidMagic { found: *b"BMP" });
+
+			let logs = get_captured_logs();
+			assert!(logs.is_empty());
+		});
+	}
+
+	#[test]
+	fn test_wrl_file_from_bytes_unsupported_version() {
+		run_test!({
+			// Arrange
+			let mut bytes = std::fs::read("test_files/reference/REF.WRL").unwrap();
+			bytes[3] = 0x02;
+
+			// Act
+			let result = WRLFile::from_bytes(&bytes);
+
+			// Assert
+			let error = result.unwrap_err();
+			assert_eq!(error, WrlError::UnsupportedVersion { version: 2 });
+			assert_eq!(error.to_string(), "Unsupported WRL format version: 2");
+
+			let logs = get_captured_logs();
+			assert!(logs.is_empty());
+		});
+	}
+
 	#[test]
 	fn test_read_wrl_file_invalid_file() {
 		run_test!({
@@ -163,6 +216,28 @@ mod tests {
 		});
 	}
 
+	#[test]
+	fn test_read_wrl_file_truncated_file() {
+		run_test!({
+			// Arrange
+			let test_file_path = PathBuf::from("test_files/reference/REF.WRL.TRIMMED");
+
+			// Act
+			let result = read_wrl_file(&test_file_path);
+
+			// Assert
+			assert_eq!(result.unwrap_err(), WrlError::Truncated {
+				section: WrlSection::PassTable,
+				expected: 256,
+				found: 255,
+			});
+
+			let logs = get_captured_logs();
+			assert_eq!(logs.len(), 1);
+			assert_eq!(logs[0], "[ERROR] Failed to read pass table from file: test_files/reference/REF.WRL.TRIMMED");
+		});
+	}
+
 	#[test]
 	fn test_read_wrl_file_nonexistent_file() {
 		run_test!({
@@ -1,96 +1,57 @@
-use byteorder::{LittleEndian, ReadBytesExt};
-use std::fs::File;
-use std::io::{Read, Seek, SeekFrom};
+use std::io::{Cursor, Read, Seek};
 use std::path::Path;
 
 use crate::consts::TILE_DATA_SIZE;
-use crate::structs::{WRLHeader};
-
-/// Reads the WRL header from the file and returns a WRLHeader struct.
-pub fn read_wrl_header(file_path: &Path) -> Result<WRLHeader, String> {
-	let mut file = match File::open(file_path) {
-		Ok(file) => file,
-		Err(_) => {
-			log::error!("Failed to open file: {}", file_path.display());
-			return Err("Failed to read WRL header".into());
+use crate::error::{WrlError, WrlSection};
+use crate::file::read_section::{read_section, read_section_u16, skip_section};
+use crate::file::wrl_view::WrlView;
+use crate::structs::{WRLFileHeader, WRLHeader};
+
+impl WRLHeader {
+	/// Parses the WRL header starting at the current position of the reader.
+	/// Bigmap and tile data are skipped, not read.
+	pub fn from_reader<R: Read + Seek>(reader: &mut R) -> Result<WRLHeader, WrlError> {
+		let header = WRLFileHeader::from_bytes(&read_section(reader, WrlSection::Header, WRLFileHeader::SIZE)?)?;
+
+		let width = read_section_u16(reader, WrlSection::Width)?;
+		let height = read_section_u16(reader, WrlSection::Height)?;
+
+		if width == 0 || height == 0 {
+			return Err(WrlError::InvalidDimensions { width, height });
 		}
-	};
 
-	match file.seek(SeekFrom::Start(5)) {
-		Ok(_) => {},
-		Err(e) => {
-			log::error!("Failed to skip header in file: {}", e);
-			return Err("Failed to read WRL header".into());
-		}
-	}
+		let minimap_size = (width as usize) * (height as usize);
+		let minimap = read_section(reader, WrlSection::Minimap, minimap_size)?;
 
-	let width = match file.read_u16::<LittleEndian>() {
-		Ok(w) => w,
-		Err(_) => {
-			log::error!("Failed to read width from file: {}", file_path.display());
-			return Err("Failed to read WRL header".into());
-		}
-	};
+		skip_section(reader, WrlSection::Bigmap, (width as u64) * (height as u64) * 2)?;
 
-	let height = match file.read_u16::<LittleEndian>() {
-		Ok(h) => h,
-		Err(_) => {
-			log::error!("Failed to read height from file: {}", file_path.display());
-			return Err("Failed to read WRL header".into());
-		}
-	};
-
-	let minimap_size = (width as usize) * (height as usize);
-    let mut minimap = vec![0; minimap_size];
-    match file.read_exact(&mut minimap) {
-		Ok(_) => {},
-		Err(_) => {
-			log::error!("Failed to read minimap from file: {}", file_path.display());
-			return Err("Failed to read WRL header".into());
-		}
-	};
+		let tile_count = read_section_u16(reader, WrlSection::TileCount)?;
 
-	match file.seek(SeekFrom::Current((width as i64) * (height as i64) * 2)) {
-		Ok(_) => {},
-		Err(e) => {
-			log::error!("Failed to skip bigmap in file: {}", e);
-			return Err("Failed to read WRL header".into());
-		}
-	};
+		skip_section(reader, WrlSection::Tiles, tile_count as u64 * TILE_DATA_SIZE as u64)?;
 
-	let tile_count = match file.read_u16::<LittleEndian>() {
-		Ok(tc) => tc,
-		Err(_) => {
-			log::error!("Failed to read tile count from file: {}", file_path.display());
-			return Err("Failed to read WRL header".into());
-		}
-	};
+		let palette_size = 256 * 3;
+		let palette = read_section(reader, WrlSection::Palette, palette_size)?;
 
-	match file.seek(SeekFrom::Current((tile_count as usize * TILE_DATA_SIZE) as i64)) {
-		Ok(_) => {},
-		Err(e) => {
-			log::error!("Failed to skip tiles in file: {}", e);
-			return Err("Failed to read WRL header".into());
-		}
-	};
-
-	let palette_size = 256 * 3;
-	let mut palette = vec![0; palette_size];
-	match file.read_exact(&mut palette) {
-		Ok(_) => {},
-		Err(e) => {
-			log::error!("Failed to read palette from file: {}", e);
-			return Err("Failed to read WRL header".into());
-		}
-	};
-
-	Ok(WRLHeader {
-		width,
-		height,
-		tile_count,
-		minimap,
-		palette,
-	})
+		Ok(WRLHeader {
+			format_version: header.version,
+			width,
+			height,
+			tile_count,
+			minimap,
+			palette,
+		})
+	}
+
+	/// Parses the WRL header from an in-memory buffer.
+	pub fn from_bytes(bytes: &[u8]) -> Result<WRLHeader, WrlError> {
+		WRLHeader::from_reader(&mut Cursor::new(bytes))
+	}
+}
+
+/// Reads the WRL header from the file and returns a WRLHeader struct.
+/// The file is memory-mapped, so the bigmap and tile data are never loaded.
+pub fn read_wrl_header(file_path: &Path) -> Result<WRLHeader, WrlError> {
+	WrlView::open(file_path).map(|view| view.to_header())
 }
 
 
@@ -113,6 +74,7 @@ mod tests {
 			// Assert
 			assert!(result.is_ok());
 			let header = result.unwrap();
+			assert_eq!(header.format_version, 1);
 			assert_eq!(header.width, 16);
 			assert_eq!(header.height, 16);
 			assert_eq!(header.tile_count, 64);
@@ -124,6 +86,48 @@ mod tests {
 		});
 	}
 
+	#[test]
+	fn test_wrl_header_from_bytes() {
+		run_test!({
+			// Arrange
+			let bytes = std::fs::read("test_files/reference/REF.WRL").unwrap();
+
+			// Act
+			let header = WRLHeader::from_bytes(&bytes).unwrap();
+
+			// Assert
+			assert_eq!(header.width, 16);
+			assert_eq!(header.height, 16);
+			assert_eq!(header.tile_count, 64);
+			assert_eq!(header.minimap, bytes[9..9 + 256]);
+			assert_eq!(header.palette, bytes[262923..263691]);
+
+			let logs = get_captured_logs();
+			assert!(logs.is_empty());
+		});
+	}
+
+	#[test]
+	fn test_wrl_header_from_bytes_truncated_tiles() {
+		run_test!({
+			// Arrange
+			let bytes = std::fs::read("test_files/reference/REF.WRL").unwrap();
+
+			// Act
+			let result = WRLHeader::from_bytes(&bytes[..1000]);
+
+			// Assert
+			assert_eq!(result.unwrap_err(), WrlError::Truncated {
+				section: WrlSection::Tiles,
+				expected: 64 * TILE_DATA_SIZE as u64,
+				found: 221,
+			});
+
+			let logs = get_captured_logs();
+			assert!(logs.is_empty());
+		});
+	}
+
 	#[test]
 	fn test_read_wrl_header_invalid_file() {
 		run_test!({
@@ -134,11 +138,46 @@ mod tests {
 			let result = read_wrl_header(&test_file_path);
 
 			// Assert
-			assert!(result.is_err());
+			assert_eq!(result.unwrap_err(), WrlError::Truncated {
+				section: WrlSection::Header,
+				expected: 5,
+				found: 0,
+			});
+
+			let logs = get_captured_logs();
+			assert_eq!(logs.len(), 1);
+			assert!(logs[0].contains("Failed to read header from file: test_files/reference/empty_file"));
+		});
+	}
+
+	#[test]
+	fn test_read_wrl_header_not_a_wrl_file() {
+		run_test!({
+			// Arrange
+			std::fs::create_dir_all("test_files/temp").expect("Failed to create temp directory");
+			let test_file_path = PathBuf::from("test_files/temp/REF.WRL.BAD_MAGIC");
+			let mut bytes = std::fs::read("test_files/reference/REF.WRL").unwrap();
+			bytes[0..3].copy_from_slice(b"BMP");
+			std::fs::write(&test_file_path, &bytes).unwrap();
+
+			// Act
+			let result = read_wrl_header(&test_file_path);
+
+			// Assert
+			assert_eq!(result.unwrap_err(), WrlError::InvalidMagic { found: *b"BMP" });
+
+			// Clean up
+			if let Err(e) = std::fs::remove_file(&test_file_path) {
+				log::error!("Failed to remove test file after test: {}", test_file_path.display());
+				log::error!("{}", e);
+			}
 
 			let logs = get_captured_logs();
 			assert_eq!(logs.len(), 1);
-			assert!(logs[0].contains("Failed to read width from file: test_files/reference/empty_file"));
+			assert_eq!(
+				logs[0],
+				"[ERROR] Failed to read WRL file: test_files/temp/REF.WRL.BAD_MAGIC: Not a WRL file: invalid header magic [42, 4D, 50]"
+			);
 		});
 	}
 
@@ -1,6 +1,7 @@
 use std::io::{Read, Seek, SeekFrom};
 use std::path::Path;
 
+use crate::error::{WrlError, WrlSection};
 use crate::file::get_file_size::get_file_size;
 use crate::file::calc_expected_file_size::calc_expected_file_size;
 use crate::file::read_wrl_header::read_wrl_header;
@@ -10,12 +11,12 @@ use crate::structs::WRLTailHeader;
 
 pub fn read_wrl_tail_header(
 	file_path: &Path,
-) -> Result<Option<WRLTailHeader>, String> {
+) -> Result<Option<WRLTailHeader>, WrlError> {
 	let wrl_header = match read_wrl_header(file_path) {
 		Ok(header) => header,
-		Err(_) => {
+		Err(e) => {
 			log::error!("Failed to read WRL header from file: {}", file_path.display());
-			return Err("Failed to read WRL tail-header".into());
+			return Err(e);
 		}
 	};
 
@@ -23,45 +24,39 @@ pub fn read_wrl_tail_header(
 
 	let file_size = match get_file_size(file_path) {
 		Ok(size) => size,
-		Err(_) => {
+		Err(e) => {
 			log::error!("Failed to get file size for: {}", file_path.display());
-			return Err("Failed to read WRL tail-header".into());
+			return Err(e);
 		}
 	};
 
 	let wrl_tail_header_length: i64 = file_size as i64 - wrl_data_length as i64;
 
 	if wrl_tail_header_length == 0 {
-		let hash_id = hash_wrl_file_without_tail(file_path)
-			.map_err(|_| "Failed to hash WRL file without tail".to_string())?;
-		return Ok(Some(WRLTailHeader {
-			_v: 1,
-			hash_id,
-			name: "".into(),
-			version: "".into(),
-			date: "".into(),
-			author: "".into(),
-			description: "".into(),
-			comment: "".into(),
-		}));
+		let hash_id = hash_wrl_file_without_tail(file_path)?;
+		return Ok(Some(WRLTailHeader::new(hash_id)));
 	}
 
 	if wrl_tail_header_length < 0 {
 		log::error!("Invalid WRL for file: {}", file_path.display());
-		return Err("Invalid WRL file".into());
+		return Err(WrlError::Truncated {
+			section: WrlSection::at_offset(wrl_header.width, wrl_header.height, wrl_header.tile_count, file_size),
+			expected: wrl_data_length,
+			found: file_size,
+		});
 	}
 
 	let mut file = match std::fs::File::open(file_path) {
 		Ok(file) => file,
-		Err(_) => {
+		Err(e) => {
 			log::error!("Failed to open file: {}", file_path.display());
-			return Err("Failed to read WRL tail-header".into());
+			return Err(e.into());
 		}
 	};
 
-	if let Err(_) = file.seek(SeekFrom::Start(wrl_data_length as u64)) {
+	if let Err(e) = file.seek(SeekFrom::Start(wrl_data_length as u64)) {
 		log::error!("Failed to seek to tail header position in file: {}", file_path.display());
-		return Err("Failed to read WRL tail-header".into());
+		return Err(e.into());
 	}
 
 	println!(
@@ -70,18 +65,19 @@ pub fn read_wrl_tail_header(
 	);
 
 	let mut buffer = String::new();
-	if let Err(_) = file.read_to_string(&mut buffer) {
+	if let Err(e) = file.read_to_string(&mut buffer) {
 		log::error!("Failed to read tail header from file: {}", file_path.display());
-		return Err("Failed to read WRL tail-header".into());
+		return Err(WrlError::InvalidTail(e.to_string()));
 	}
 
-	let tail_header = match serde_json::from_str(&buffer) {
+	let tail_header = match WRLTailHeader::from_json(&buffer) {
 		Ok(header) => header,
-		Err(e) => {
+		Err(WrlError::InvalidTail(message)) => {
 			log::error!("Failed to deserialize WRL tail header: {}", file_path.display());
-			log::error!("{}", e);
-			return Err("Failed to read WRL tail-header".into());
+			log::error!("{}", message);
+			return Err(WrlError::InvalidTail(message));
 		}
+		Err(e) => return Err(e),
 	};
 
 	Ok(Some(tail_header))
@@ -93,6 +89,7 @@ mod tests {
 	use super::*;
 
 	use crate::run_test;
+	use crate::structs::WRLChangelogEntry;
 
 	#[test]
 	fn test_read_wrl_tail_header() {
@@ -104,14 +101,48 @@ mod tests {
 			let tail_header = read_wrl_tail_header(file_path).unwrap().unwrap();
 
 			// Assert
-			assert_eq!(tail_header._v, 1);
+			// Version 1 tail header is upgraded to the current version.
+			assert_eq!(tail_header._v, 2);
 			assert_eq!(tail_header.hash_id, "1234567890abcdef");
 			assert_eq!(tail_header.name, "custom map name");
 			assert_eq!(tail_header.version, "12.0");
 			assert_eq!(tail_header.date, "2456-12-02 14:23:12");
-			assert_eq!(tail_header.author, "who made this world?");
+			assert_eq!(tail_header.authors, vec!["who made this world?".to_string()]);
 			assert_eq!(tail_header.description, "custom map description");
 			assert_eq!(tail_header.comment, "user comment");
+			assert!(tail_header.tags.is_empty());
+			assert_eq!(tail_header.player_count, None);
+			assert!(tail_header.changelog.is_empty());
+			assert_eq!(tail_header.homepage, "");
+			assert_eq!(tail_header.original_file_name, "");
+
+			let logs = get_captured_logs();
+			assert!(logs.is_empty());
+		});
+	}
+
+	#[test]
+	fn test_read_wrl_tail_header_v2() {
+		run_test!({
+			// Arrange
+			let file_path = Path::new("test_files/reference/REF.WRL.TAIL.V2");
+
+			// Act
+			let tail_header = read_wrl_tail_header(file_path).unwrap().unwrap();
+
+			// Assert
+			assert_eq!(tail_header._v, 2);
+			assert_eq!(tail_header.hash_id, "1234567890abcdef");
+			assert_eq!(tail_header.authors, vec!["who made this world?".to_string(), "second author".to_string()]);
+			assert_eq!(tail_header.tags, vec!["islands".to_string(), "naval".to_string()]);
+			assert_eq!(tail_header.player_count, Some(4));
+			assert_eq!(tail_header.changelog, vec![WRLChangelogEntry {
+				version: "12.0".to_string(),
+				date: "2456-12-02 14:23:12".to_string(),
+				changes: "moved the islands".to_string(),
+			}]);
+			assert_eq!(tail_header.homepage, "https://example.com/maps/custom");
+			assert_eq!(tail_header.original_file_name, "CUSTOM.WRL");
 
 			let logs = get_captured_logs();
 			assert!(logs.is_empty());
@@ -128,12 +159,12 @@ mod tests {
 			let tail_header = read_wrl_tail_header(file_path).unwrap().unwrap();
 
 			// Assert
-			assert_eq!(tail_header._v, 1);
+			assert_eq!(tail_header._v, 2);
 			assert_eq!(tail_header.hash_id, "93bcb82dc19b0d7a9a6eb9faf54869c9d3e353e0fdf4a847096ceff8720614aa");
 			assert_eq!(tail_header.name, "");
 			assert_eq!(tail_header.version, "");
 			assert_eq!(tail_header.date, "");
-			assert_eq!(tail_header.author, "");
+			assert!(tail_header.authors.is_empty());
 			assert_eq!(tail_header.description, "");
 			assert_eq!(tail_header.comment, "");
 
@@ -152,7 +183,10 @@ mod tests {
 			let tail_header = read_wrl_tail_header(file_path);
 
 			// Assert
-			assert!(tail_header.is_err());
+			assert_eq!(
+				tail_header.unwrap_err(),
+				WrlError::InvalidTail("EOF while parsing a string at line 7 column 12".to_string()),
+			);
 
 			let logs = get_captured_logs();
 			assert_eq!(logs.len(), 2);
@@ -178,4 +212,24 @@ mod tests {
 			assert_eq!(logs[0], "[ERROR] Invalid WRL for file: test_files/reference/REF.WRL.TRIMMED");
 		});
 	}
+
+	#[test]
+	fn test_wrl_tail_header_from_json_unsupported_version() {
+		run_test!({
+			// Arrange
+			let json = r#"{"_v":3,"hash_id":"1234567890abcdef"}"#;
+
+			// Act
+			let tail_header = WRLTailHeader::from_json(json);
+
+			// Assert
+			assert_eq!(
+				tail_header.unwrap_err(),
+				WrlError::InvalidTail("Unsupported tail header version: 3".to_string()),
+			);
+
+			let logs = get_captured_logs();
+			assert!(logs.is_empty());
+		});
+	}
 }
@@ -1,25 +1,25 @@
 use std::path::Path;
 
 use crate::{
-    verify_file_content, verify_tail_header
+    read_wrl_tail_header, verify_file_content, verify_tail_header, WrlError
 };
 
-pub fn verify_file(file_path: &Path) -> Result<bool, String> {
+/// Verifies the WRL structure of the file and its tail header, if any.
+/// A tail header with a stale `hash_id` is reported as `WrlError::HashMismatch`.
+pub fn verify_file(file_path: &Path) -> Result<bool, WrlError> {
     let file_size = match file_path.metadata() {
         Ok(metadata) => metadata.len(),
-        Err(_) => {
-			let error_message = format!("Failed to get file metadata: {}", file_path.display());
-			log::error!("{}", error_message);
-			return Err(error_message);
+        Err(e) => {
+			log::error!("Failed to get file metadata: {}", file_path.display());
+			return Err(e.into());
         }
     };
 
     let wrl_header = match crate::read_wrl_header(file_path) {
         Ok(header) => header,
-        Err(_) => {
-			let error_message = format!("Failed to read WRL header from file: {}", file_path.display());
-			log::error!("{}", error_message);
-			return Err(error_message);
+        Err(e) => {
+			log::error!("Failed to read WRL header from file: {}", file_path.display());
+			return Err(e);
         }
     };
 
@@ -28,20 +28,68 @@ pub fn verify_file(file_path: &Path) -> Result<bool, String> {
 
     if file_size >= expected_file_size {
 		if let Err(e) = verify_file_content(file_path) {
-			let error_message = format!("Invalid WRL file structure: {}: {}", file_path.display(), e);
-			log::error!("{}", error_message);
-			return Err(error_message);
+			log::error!("Invalid WRL file structure: {}: {}", file_path.display(), e);
+			return Err(e);
 		}
     }
 
 	if file_size > expected_file_size {
-        let is_tail_valid = verify_tail_header(file_path);
-        if !is_tail_valid {
-			let error_message = format!("Invalid tail header in file: {}", file_path.display());
-			log::error!("{}", error_message);
-			return Err(error_message);
-        }
-    }
+		if let Err(e) = read_wrl_tail_header(file_path) {
+			log::error!("Invalid tail header in file: {}", file_path.display());
+			return match e {
+				WrlError::InvalidTail(_) => Err(e),
+				_ => Err(WrlError::InvalidTail(e.to_string())),
+			};
+		}
+
+		verify_tail_header(file_path)?;
+	}
 
     Ok(true)
 }
+
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	use crate::run_test;
+
+	#[test]
+	fn test_verify_file() {
+		run_test!({
+			// Arrange
+			let file_path = Path::new("test_files/reference/REF.WRL");
+
+			// Act
+			let result = verify_file(file_path);
+
+			// Assert
+			assert_eq!(result, Ok(true));
+
+			let logs = get_captured_logs();
+			assert!(logs.is_empty());
+		});
+	}
+
+	#[test]
+	fn test_verify_file_stale_tail_header() {
+		run_test!({
+			// Arrange
+			let file_path = Path::new("test_files/reference/REF.WRL.TAIL");
+
+			// Act
+			let result = verify_file(file_path);
+
+			// Assert
+			assert_eq!(result.unwrap_err(), WrlError::HashMismatch {
+				expected: "93bcb82dc19b0d7a9a6eb9faf54869c9d3e353e0fdf4a847096ceff8720614aa".to_string(),
+				found: "1234567890abcdef".to_string(),
+			});
+
+			let logs = get_captured_logs();
+			assert_eq!(logs.len(), 1);
+			assert_eq!(logs[0], "[ERROR] Tail header hash_id does not match the content of file: test_files/reference/REF.WRL.TAIL");
+		});
+	}
+}
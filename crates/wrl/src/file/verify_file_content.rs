@@ -1,95 +1,100 @@
-use std::{fs::File, io::{Read, Seek, SeekFrom}, path::Path};
-
-use byteorder::{LittleEndian, ReadBytesExt};
+use std::{fs::File, io::{Cursor, Read, Seek}, path::Path};
 
 use crate::TILE_DATA_SIZE;
+use crate::error::{WrlError, WrlSection};
+use crate::file::read_section::{log_read_error, read_section, read_section_u16, skip_section};
+use crate::structs::WRLFileHeader;
+
+/// Verifies that all WRL sections are present, starting at the current position of the reader.
+pub fn verify_wrl_content<R: Read + Seek>(reader: &mut R) -> Result<bool, WrlError> {
+	WRLFileHeader::from_bytes(&read_section(reader, WrlSection::Header, WRLFileHeader::SIZE)?)?;
+
+	let width = read_section_u16(reader, WrlSection::Width)?;
+	let height = read_section_u16(reader, WrlSection::Height)?;
+
+	if width == 0 || height == 0 {
+		return Err(WrlError::InvalidDimensions { width, height });
+	}
+
+	let minimap_size = (width as u64) * (height as u64);
+	skip_section(reader, WrlSection::Minimap, minimap_size)?;
+
+	let bigmap_size = (width as u64) * (height as u64) * 2;
+	skip_section(reader, WrlSection::Bigmap, bigmap_size)?;
+
+	let tile_count = read_section_u16(reader, WrlSection::TileCount)?;
+
+	let tiles_size = tile_count as u64 * TILE_DATA_SIZE as u64;
+	skip_section(reader, WrlSection::Tiles, tiles_size)?;
 
-pub fn verify_file_content(file_path: &Path) -> Result<bool, String> {
+	let palette_size = 256 * 3;
+	skip_section(reader, WrlSection::Palette, palette_size)?;
+
+	let pass_table_size = tile_count as u64;
+	skip_section(reader, WrlSection::PassTable, pass_table_size)?;
+
+	Ok(true)
+}
+
+/// Verifies that all WRL sections are present in the in-memory buffer.
+pub fn verify_wrl_bytes(bytes: &[u8]) -> Result<bool, WrlError> {
+	verify_wrl_content(&mut Cursor::new(bytes))
+}
+
+pub fn verify_file_content(file_path: &Path) -> Result<bool, WrlError> {
 	let mut file = match File::open(file_path) {
 		Ok(file) => file,
-		Err(_) => {
-			let error_message = format!("Failed to open file: {}", file_path.display());
-			log::error!("{}", error_message);
-			return Err(error_message);
+		Err(e) => {
+			log::error!("Failed to open file: {}", file_path.display());
+			return Err(e.into());
 		}
 	};
 
-	let header_size = 5;
-	let mut header = vec![0; header_size];
-	match file.read_exact(&mut header) {
-		Ok(_) => {},
-		Err(e) => {
-			let error_message = format!("Failed to read WRL header: {}", e);
-			log::error!("{}", error_message);
-			return Err(error_message);
-		}
-	}
+	verify_wrl_content(&mut file).inspect_err(|e| log_read_error(file_path, e))
+}
 
-	let width = match file.read_u16::<LittleEndian>() {
-		Ok(w) => w,
-		Err(_) => {
-			let error_message = format!("Failed to read width from file: {}", file_path.display());
-			log::error!("{}", error_message);
-			return Err(error_message);
-		}
-	};
 
-	let height = match file.read_u16::<LittleEndian>() {
-		Ok(h) => h,
-		Err(_) => {
-			let error_message = format!("Failed to read height from file: {}", file_path.display());
-			log::error!("{}", error_message);
-			return Err(error_message);
-		}
-	};
+#[cfg(test)]
+mod tests {
+	use super::*;
 
-	{
-		let minimap_size = (width as usize) * (height as usize);
-		file.seek(SeekFrom::Current(minimap_size as i64)).map_err(|e| {
-			let errror_message = format!("Failed to seek past minimap in file: {}", e);
-			log::error!("{}", errror_message);
-			errror_message
-		})?;
-	}
+	use crate::run_test;
 
-	{
-		let bigmap_size = (width as usize) * (height as usize) * 2;
-		file.seek(SeekFrom::Current(bigmap_size as i64)).map_err(|e| {
-			let error_message = format!("Failed to seek past bigmap in file: {}", e);
-			log::error!("{}", error_message);
-			error_message
-		})?;
+	#[test]
+	fn test_verify_file_content() {
+		run_test!({
+			// Arrange
+			let file_path = Path::new("test_files/reference/REF.WRL");
+
+			// Act
+			let result = verify_file_content(file_path);
+
+			// Assert
+			assert_eq!(result, Ok(true));
+
+			let logs = get_captured_logs();
+			assert!(logs.is_empty());
+		});
 	}
 
-	let tile_count = match file.read_u16::<LittleEndian>() {
-		Ok(tc) => tc,
-		Err(_) => {
-			let error_message = format!("Failed to read tile count from file: {}", file_path.display());
-			log::error!("{}", error_message);
-			return Err(error_message);
-		}
-	};
+	#[test]
+	fn test_verify_wrl_bytes_truncated() {
+		run_test!({
+			// Arrange
+			let bytes = std::fs::read("test_files/reference/REF.WRL.TRIMMED").unwrap();
 
-	let tiles_size = tile_count as usize * TILE_DATA_SIZE;
-	file.seek(SeekFrom::Current(tiles_size as i64)).map_err(|e| {
-		let error_message = format!("Failed to seek past tiles in file: {}", e);
-		log::error!("{}", error_message);
-		error_message
-	})?;
+			// Act
+			let result = verify_wrl_bytes(&bytes);
 
-	let palette_size = 256 * 3;
-	file.seek(SeekFrom::Current(palette_size as i64)).map_err(|e| {
-		let error_message = format!("Failed to seek past palette in file: {}", e);
-		log::error!("{}", error_message);
-		error_message
-	})?;
-
-	let pass_table_size = tile_count as usize;
-	file.seek(SeekFrom::Current(pass_table_size as i64)).map_err(|e| {
-		let error_message = format!("Failed to seek past passability table in file: {}", e);
-		log::error!("{}", error_message);
-		error_message
-	})?;
+			// Assert
+			assert_eq!(result, Err(WrlError::Truncated {
+				section: WrlSection::PassTable,
+				expected: 256,
+				found: 255,
+			}));
 
-	Ok(true)
+			let logs = get_captured_logs();
+			assert!(logs.is_empty());
+		});
+	}
 }
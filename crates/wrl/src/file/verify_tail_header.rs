@@ -1,15 +1,74 @@
 use std::path::Path;
 
+use crate::error::WrlError;
+use crate::hash_wrl_file_without_tail::hash_wrl_file_without_tail;
 use crate::read_wrl_tail_header;
 
-pub fn verify_tail_header(file_path: &Path) -> bool {
-	let _ = match read_wrl_tail_header(file_path) {
-		Ok(header) => header,
-		Err(_) => {
-			log::error!("Failed to read tail header of file: {}", file_path.display());
-			return false;
-		}
+/// Checks that the `hash_id` stored in the tail header matches the WRL content.
+/// A mismatch means the map was edited without updating its tail header, it is returned as
+/// `WrlError::HashMismatch`, which `fix_tail_header_hash` can repair.
+/// Files without a tail header always match.
+pub fn verify_tail_header(file_path: &Path) -> Result<(), WrlError> {
+	let tail_header = match read_wrl_tail_header(file_path)? {
+		Some(tail_header) => tail_header,
+		None => return Ok(()),
 	};
 
-	true
+	let hash_id = hash_wrl_file_without_tail(file_path)?;
+
+	if tail_header.hash_id != hash_id {
+		log::error!("Tail header hash_id does not match the content of file: {}", file_path.display());
+		return Err(WrlError::HashMismatch {
+			expected: hash_id,
+			found: tail_header.hash_id,
+		});
+	}
+
+	Ok(())
+}
+
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	use crate::run_test;
+
+	#[test]
+	fn test_verify_tail_header_without_tail() {
+		run_test!({
+			// Arrange
+			let file_path = Path::new("test_files/reference/REF.WRL");
+
+			// Act
+			let result = verify_tail_header(file_path);
+
+			// Assert
+			assert_eq!(result, Ok(()));
+
+			let logs = get_captured_logs();
+			assert!(logs.is_empty());
+		});
+	}
+
+	#[test]
+	fn test_verify_tail_header_hash_mismatch() {
+		run_test!({
+			// Arrange
+			let file_path = Path::new("test_files/reference/REF.WRL.TAIL");
+
+			// Act
+			let result = verify_tail_header(file_path);
+
+			// Assert
+			assert_eq!(result.unwrap_err(), WrlError::HashMismatch {
+				expected: "93bcb82dc19b0d7a9a6eb9faf54869c9d3e353e0fdf4a847096ceff8720614aa".to_string(),
+				found: "1234567890abcdef".to_string(),
+			});
+
+			let logs = get_captured_logs();
+			assert_eq!(logs.len(), 1);
+			assert_eq!(logs[0], "[ERROR] Tail header hash_id does not match the content of file: test_files/reference/REF.WRL.TAIL");
+		});
+	}
 }
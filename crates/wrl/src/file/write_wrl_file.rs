@@ -0,0 +1,188 @@
+use std::fs::File;
+use std::io::Write;
+use std::path::Path;
+
+use crate::error::WrlError;
+use crate::file::calc_expected_file_size::calc_expected_file_size;
+use crate::structs::{WRLFile, WRLTailHeader};
+
+
+impl WRLFile {
+	/// Serializes the WRL file into the on-disk byte layout, without a tail header.
+	pub fn to_bytes(&self) -> Vec<u8> {
+		let expected_file_size = calc_expected_file_size(self.width, self.height, self.tile_count);
+		let mut bytes = Vec::with_capacity(expected_file_size as usize);
+
+		bytes.extend_from_slice(&self.header.to_bytes());
+		bytes.extend_from_slice(&self.width.to_le_bytes());
+		bytes.extend_from_slice(&self.height.to_le_bytes());
+		bytes.extend_from_slice(&self.minimap);
+		for tile_index in &self.bigmap {
+			bytes.extend_from_slice(&tile_index.to_le_bytes());
+		}
+		bytes.extend_from_slice(&self.tile_count.to_le_bytes());
+		bytes.extend_from_slice(&self.tiles);
+		bytes.extend_from_slice(&self.palette);
+		bytes.extend_from_slice(&self.pass_table);
+
+		bytes
+	}
+}
+
+/// Writes the WRL file to disk, optionally followed by a tail header.
+pub fn write_wrl_file(
+	file_path: &Path,
+	wrl_file: &WRLFile,
+	tail_header: Option<&WRLTailHeader>,
+) -> Result<(), WrlError> {
+	if let Err(e) = wrl_file.check_sections_size() {
+		log::error!("Invalid WRL data for file: {}: {}", file_path.display(), e);
+		return Err(e);
+	}
+
+	let mut bytes = wrl_file.to_bytes();
+
+	if let Some(tail_header) = tail_header {
+		match tail_header.to_json() {
+			Ok(json) => bytes.extend_from_slice(json.as_bytes()),
+			Err(e) => {
+				log::error!("Failed to serialize tail header to JSON: {}", file_path.display());
+				log::error!("{}", e);
+				return Err(e);
+			}
+		}
+	}
+
+	let mut file = match File::create(file_path) {
+		Ok(file) => file,
+		Err(e) => {
+			log::error!("Failed to create file: {}", file_path.display());
+			log::error!("{}", e);
+			return Err(e.into());
+		}
+	};
+
+	if let Err(e) = file.write_all(&bytes).and_then(|_| file.flush()) {
+		log::error!("Failed to write WRL file: {}", file_path.display());
+		log::error!("{}", e);
+		return Err(e.into());
+	}
+
+	Ok(())
+}
+
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use std::path::PathBuf;
+
+	use crate::error::WrlSection;
+	use crate::run_test;
+	use crate::{read_wrl_file, read_wrl_tail_header};
+
+	#[test]
+	fn test_wrl_file_to_bytes_round_trip() {
+		run_test!({
+			// Arrange
+			let path = PathBuf::from("test_files/reference/REF.WRL");
+			let ref_content = std::fs::read(&path).expect("TEST INTERRUPTED! Failed to read reference file");
+			let wrl_file = read_wrl_file(&path).unwrap();
+
+			// Act
+			let bytes = wrl_file.to_bytes();
+
+			// Assert
+			assert_eq!(bytes, ref_content);
+
+			let logs = get_captured_logs();
+			assert!(logs.is_empty());
+		});
+	}
+
+	#[test]
+	fn test_write_wrl_file() {
+		run_test!({
+			// Arrange
+			std::fs::create_dir_all("test_files/temp").expect("Failed to create temp directory");
+			let ref_file_path = PathBuf::from("test_files/reference/REF.WRL");
+			let file_path = PathBuf::from("test_files/temp/REF.WRL.WRITE");
+			let wrl_file = read_wrl_file(&ref_file_path).unwrap();
+
+			// Act
+			let result = write_wrl_file(&file_path, &wrl_file, None);
+
+			// Assert
+			assert!(result.is_ok());
+
+			let ref_content = std::fs::read(&ref_file_path).expect("TEST INTERRUPTED! Failed to read reference file");
+			let test_content = std::fs::read(&file_path).expect("TEST INTERRUPTED! Failed to read test output file");
+			assert_eq!(ref_content, test_content, "File content does not match reference");
+
+			// Clean up
+			if let Err(e) = std::fs::remove_file(&file_path) {
+				log::error!("Failed to remove test file after test: {}", file_path.display());
+				log::error!("{}", e);
+			}
+
+			let logs = get_captured_logs();
+			assert!(logs.is_empty());
+		});
+	}
+
+	#[test]
+	fn test_write_wrl_file_with_tail() {
+		run_test!({
+			// Arrange
+			std::fs::create_dir_all("test_files/temp").expect("Failed to create temp directory");
+			let ref_file_path = PathBuf::from("test_files/reference/REF.WRL.TAIL.V2");
+			let file_path = PathBuf::from("test_files/temp/REF.WRL.TAIL.WRITE");
+			let wrl_file = read_wrl_file(&ref_file_path).unwrap();
+			let tail_header = read_wrl_tail_header(&ref_file_path).unwrap().unwrap();
+
+			// Act
+			let result = write_wrl_file(&file_path, &wrl_file, Some(&tail_header));
+
+			// Assert
+			assert!(result.is_ok());
+
+			let ref_content = std::fs::read(&ref_file_path).expect("TEST INTERRUPTED! Failed to read reference file");
+			let test_content = std::fs::read(&file_path).expect("TEST INTERRUPTED! Failed to read test output file");
+			assert_eq!(ref_content, test_content, "File content does not match reference");
+
+			// Clean up
+			if let Err(e) = std::fs::remove_file(&file_path) {
+				log::error!("Failed to remove test file after test: {}", file_path.display());
+				log::error!("{}", e);
+			}
+
+			let logs = get_captured_logs();
+			assert!(logs.is_empty());
+		});
+	}
+
+	#[test]
+	fn test_write_wrl_file_invalid_sections() {
+		run_test!({
+			// Arrange
+			let file_path = PathBuf::from("test_files/temp/REF.WRL.INVALID.WRITE");
+			let mut wrl_file = read_wrl_file(&PathBuf::from("test_files/reference/REF.WRL")).unwrap();
+			wrl_file.minimap.pop();
+
+			// Act
+			let result = write_wrl_file(&file_path, &wrl_file, None);
+
+			// Assert
+			assert_eq!(result.unwrap_err(), WrlError::InvalidSectionSize {
+				section: WrlSection::Minimap,
+				expected: 256,
+				found: 255,
+			});
+			assert!(!file_path.exists());
+
+			let logs = get_captured_logs();
+			assert_eq!(logs.len(), 1);
+			assert_eq!(logs[0], "[ERROR] Invalid WRL data for file: test_files/temp/REF.WRL.INVALID.WRITE: Invalid minimap section size: expected 256 bytes, found 255");
+		});
+	}
+}
@@ -1,6 +1,7 @@
 use std::path::Path;
 use std::io::Write;
 
+use crate::error::WrlError;
 use crate::file::copy_wrl_file_without_tail;
 use crate::structs::WRLTailHeader;
 
@@ -15,7 +16,7 @@ use crate::structs::WRLTailHeader;
 pub fn write_wrl_tail_header(
 	file_path: &Path,
 	tail_header: &WRLTailHeader,
-) -> Result<(), ()> {
+) -> Result<(), WrlError> {
 	// Get current time as temp file suffix.
 	let current_time = chrono::Local::now();
 	let temp_file_suffix = format!("{}", current_time.format("%Y%m%d%H%M%S"));
@@ -23,14 +24,13 @@ pub fn write_wrl_tail_header(
 	let original_backup_path = file_path.with_extension(format!("WRL.~original~.{}", temp_file_suffix));
 
 	// Copy the original file without the tail header to temp file.
-	copy_wrl_file_without_tail(file_path, &temp_file_path).map_err(|_| {
+	copy_wrl_file_without_tail(file_path, &temp_file_path).inspect_err(|_| {
 		log::error!("Failed to copy WRL file without tail: {}. Aborting.", file_path.display());
-		()
 	})?;
 
 	// Write the new tail header to the temp file.
 	// If this fails, temp file will be removed.
-	let json = match serde_json::to_string(tail_header) {
+	let json = match tail_header.to_json() {
 		Ok(j) => j,
 		Err(e) => {
 			log::error!("Failed to serialize tail header to JSON. Reverting changes.");
@@ -40,7 +40,7 @@ pub fn write_wrl_tail_header(
 				log::error!("{}", e2);
 				log::info!("Temp file: {} was not removed. Please remove it manually if not needed.", temp_file_path.display());
 			}
-			return Err(());
+			return Err(e);
 		}
 	};
 
@@ -48,9 +48,9 @@ pub fn write_wrl_tail_header(
 		.write(true)
 		.append(true)
 		.open(&temp_file_path)
-		.map_err(|_| {
+		.map_err(|e| {
 			log::error!("Failed to open temp file for writing: {}. Aborting.", temp_file_path.display());
-			()
+			WrlError::from(e)
 		})?;
 
 	if let Err(e) = write!(&mut file, "{}", json) {
@@ -61,7 +61,7 @@ pub fn write_wrl_tail_header(
 			log::error!("{}", e);
 			log::info!("Temp file: {} was not removed. Please remove it manually if not needed.", temp_file_path.display());
 		}
-		()
+		return Err(e.into());
 	};
 
 	// Rename the original file to a backup and then replace it with the temp file.
@@ -74,7 +74,7 @@ pub fn write_wrl_tail_header(
 			log::error!("{}", e);
 			log::info!("Temp file: {} was not removed. Please remove it manually if not needed.", temp_file_path.display());
 		}
-		return Err(());
+		return Err(e.into());
 	}
 
 	// Replace the original file with the temp file.
@@ -92,7 +92,7 @@ pub fn write_wrl_tail_header(
 			log::error!("{}", e3);
 			log::info!("Temp file: {} was not removed. Please remove it manually if not needed.", temp_file_path.display());
 		}
-		return Err(());
+		return Err(e.into());
 	}
 
 	// Clean up: remove the backup of the original file.
@@ -113,6 +113,7 @@ mod tests {
 	use std::path::PathBuf;
 
 	use crate::run_test;
+	use crate::structs::WRLChangelogEntry;
 
 	#[test]
 	fn test_write_wrl_tail_header() {
@@ -120,21 +121,30 @@ mod tests {
 			// Arrange
 			std::fs::create_dir_all("test_files/temp").expect("Failed to create temp directory");
 			let source_file_path = PathBuf::from("test_files/reference/REF.WRL");
-			let ref_file_path = PathBuf::from("test_files/reference/REF.WRL.TAIL");
+			let ref_file_path = PathBuf::from("test_files/reference/REF.WRL.TAIL.V2");
 			let file_path = PathBuf::from("test_files/temp/REF.WRL.TAIL.TEST");
 			if let Err(e) = std::fs::copy(&source_file_path, &file_path) {
 				log::error!("TEST INTERRUPTED! Failed to copy {} to {}: {}", source_file_path.display(), file_path.display(), e);
 				return;
 			}
 			let tail_header = WRLTailHeader {
-				_v: 1,
+				_v: 2,
 				hash_id: "1234567890abcdef".to_string(),
 				name: "custom map name".to_string(),
 				version: "12.0".to_string(),
 				date: "2456-12-02 14:23:12".to_string(),
-				author: "who made this world?".to_string(),
+				authors: vec!["who made this world?".to_string(), "second author".to_string()],
 				description: "custom map description".to_string(),
 				comment: "user comment".to_string(),
+				tags: vec!["islands".to_string(), "naval".to_string()],
+				player_count: Some(4),
+				changelog: vec![WRLChangelogEntry {
+					version: "12.0".to_string(),
+					date: "2456-12-02 14:23:12".to_string(),
+					changes: "moved the islands".to_string(),
+				}],
+				homepage: "https://example.com/maps/custom".to_string(),
+				original_file_name: "CUSTOM.WRL".to_string(),
 			};
 
 			// Act
@@ -165,16 +175,7 @@ mod tests {
 		run_test!({
 			// Arrange
 			let file_path = PathBuf::from("test_files/temp/REF.WRL.TAIL.NON_EXISTING");
-			let tail_header = WRLTailHeader {
-				_v: 1,
-				hash_id: "1234567890abcdef".to_string(),
-				name: "custom map name".to_string(),
-				version: "12.0".to_string(),
-				date: "2456-12-02 14:23:12".to_string(),
-				author: "who made this world?".to_string(),
-				description: "custom map description".to_string(),
-				comment: "user comment".to_string(),
-			};
+			let tail_header = WRLTailHeader::new("1234567890abcdef".to_string());
 
 			// Act
 			let result = write_wrl_tail_header(&file_path, &tail_header);
@@ -188,4 +189,39 @@ mod tests {
 			assert_eq!(logs[1], "[ERROR] Failed to copy WRL file without tail: test_files/temp/REF.WRL.TAIL.NON_EXISTING. Aborting.");
 		});
 	}
+
+	#[test]
+	fn test_write_wrl_tail_header_upgrades_version() {
+		run_test!({
+			// Arrange
+			std::fs::create_dir_all("test_files/temp").expect("Failed to create temp directory");
+			let source_file_path = PathBuf::from("test_files/reference/REF.WRL.TAIL");
+			let file_path = PathBuf::from("test_files/temp/REF.WRL.TAIL.UPGRADE");
+			if let Err(e) = std::fs::copy(&source_file_path, &file_path) {
+				log::error!("TEST INTERRUPTED! Failed to copy {} to {}: {}", source_file_path.display(), file_path.display(), e);
+				return;
+			}
+			let tail_header = crate::read_wrl_tail_header(&file_path).unwrap().unwrap();
+
+			// Act
+			let result = write_wrl_tail_header(&file_path, &tail_header);
+
+			// Assert
+			assert!(result.is_ok());
+
+			let content = std::fs::read(&file_path).expect("TEST INTERRUPTED! Failed to read test output file");
+			let tail = String::from_utf8_lossy(&content[263755..]).to_string();
+			assert!(tail.starts_with(r#"{"_v":2,"hash_id":"1234567890abcdef","#));
+			assert!(tail.contains(r#""authors":["who made this world?"]"#));
+
+			// Clean up
+			if let Err(e) = std::fs::remove_file(&file_path) {
+				log::error!("Failed to remove test file after test: {}", file_path.display());
+				log::error!("{}", e);
+			}
+
+			let logs = get_captured_logs();
+			assert!(logs.is_empty());
+		});
+	}
 }
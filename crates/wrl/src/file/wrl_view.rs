@@ -0,0 +1,324 @@
+use std::fs::File;
+use std::ops::Range;
+use std::path::Path;
+
+use memmap2::Mmap;
+
+use crate::consts::TILE_DATA_SIZE;
+use crate::error::{WrlError, WrlSection};
+use crate::file::read_section::log_read_error;
+use crate::structs::{WRLFile, WRLFileHeader, WRLHeader};
+
+const PALETTE_SIZE: usize = 256 * 3;
+
+/// Byte ranges of the WRL sections, up to and including the palette.
+#[derive(Debug, Clone, PartialEq)]
+struct WrlLayout {
+	header: WRLFileHeader,
+	width: u16,
+	height: u16,
+	tile_count: u16,
+	minimap: Range<usize>,
+	bigmap: Range<usize>,
+	tiles: Range<usize>,
+	palette: Range<usize>,
+}
+
+impl WrlLayout {
+	/// Locates the sections in the WRL data. Like `WRLHeader::from_reader`, the header
+	/// magic and version are checked and the pass table may be truncated.
+	fn parse(bytes: &[u8]) -> Result<WrlLayout, WrlError> {
+		let mut offset = 0;
+		let mut section = |section: WrlSection, size: usize| -> Result<Range<usize>, WrlError> {
+			let start = offset.min(bytes.len());
+			let available = bytes.len() - start;
+			if available < size {
+				return Err(WrlError::Truncated {
+					section,
+					expected: size as u64,
+					found: available as u64,
+				});
+			}
+			offset = start + size;
+			Ok(start..offset)
+		};
+		let read_u16 = |range: Range<usize>| u16::from_le_bytes([bytes[range.start], bytes[range.start + 1]]);
+
+		let header = WRLFileHeader::from_bytes(&bytes[section(WrlSection::Header, WRLFileHeader::SIZE)?])?;
+		let width = read_u16(section(WrlSection::Width, 2)?);
+		let height = read_u16(section(WrlSection::Height, 2)?);
+
+		if width == 0 || height == 0 {
+			return Err(WrlError::InvalidDimensions { width, height });
+		}
+
+		let cell_count = width as usize * height as usize;
+		let minimap = section(WrlSection::Minimap, cell_count)?;
+		let bigmap = section(WrlSection::Bigmap, cell_count * 2)?;
+		let tile_count = read_u16(section(WrlSection::TileCount, 2)?);
+		let tiles = section(WrlSection::Tiles, tile_count as usize * TILE_DATA_SIZE)?;
+		let palette = section(WrlSection::Palette, PALETTE_SIZE)?;
+
+		Ok(WrlLayout {
+			header,
+			width,
+			height,
+			tile_count,
+			minimap,
+			bigmap,
+			tiles,
+			palette,
+		})
+	}
+
+	fn pass_table(&self) -> Range<usize> {
+		self.palette.end..self.palette.end + self.tile_count as usize
+	}
+}
+
+/// Read-only view of a memory-mapped WRL file.
+/// Sections are borrowed from the mapping, so only the pages which are accessed get loaded.
+#[derive(Debug)]
+pub struct WrlView {
+	mmap: Mmap,
+	layout: WrlLayout,
+}
+
+impl WrlView {
+	/// Maps the file into memory and locates its sections.
+	/// A truncated pass table is reported only by the accessors which need it.
+	pub fn open(file_path: &Path) -> Result<WrlView, WrlError> {
+		let file = match File::open(file_path) {
+			Ok(file) => file,
+			Err(e) => {
+				log::error!("Failed to open file: {}", file_path.display());
+				return Err(e.into());
+			}
+		};
+
+		// The file is only read, modifying it while mapped is not supported.
+		let mmap = match unsafe { Mmap::map(&file) } {
+			Ok(mmap) => mmap,
+			Err(e) => {
+				log::error!("Failed to map file: {}", file_path.display());
+				return Err(e.into());
+			}
+		};
+
+		let layout = WrlLayout::parse(&mmap).inspect_err(|e| log_read_error(file_path, e))?;
+
+		Ok(WrlView { mmap, layout })
+	}
+
+	pub fn width(&self) -> u16 {
+		self.layout.width
+	}
+
+	pub fn height(&self) -> u16 {
+		self.layout.height
+	}
+
+	pub fn tile_count(&self) -> u16 {
+		self.layout.tile_count
+	}
+
+	/// Size of the whole file, including a tail header.
+	pub fn file_size(&self) -> u64 {
+		self.mmap.len() as u64
+	}
+
+	pub fn header(&self) -> WRLFileHeader {
+		self.layout.header
+	}
+
+	pub fn minimap(&self) -> &[u8] {
+		&self.mmap[self.layout.minimap.clone()]
+	}
+
+	/// Raw bigmap section, two little-endian bytes per cell.
+	pub fn bigmap_bytes(&self) -> &[u8] {
+		&self.mmap[self.layout.bigmap.clone()]
+	}
+
+	/// Decodes the tile indices of all bigmap cells.
+	pub fn bigmap(&self) -> Vec<u16> {
+		self.bigmap_bytes()
+			.chunks_exact(2)
+			.map(|pair| u16::from_le_bytes([pair[0], pair[1]]))
+			.collect()
+	}
+
+	/// Returns the tile index of the map cell.
+	pub fn cell_tile(&self, x: u16, y: u16) -> Option<u16> {
+		if x >= self.width() || y >= self.height() {
+			return None;
+		}
+
+		let offset = (y as usize * self.width() as usize + x as usize) * 2;
+		let bytes = self.bigmap_bytes();
+		Some(u16::from_le_bytes([bytes[offset], bytes[offset + 1]]))
+	}
+
+	/// Tile data of all tiles, one palette index per pixel.
+	pub fn tiles(&self) -> &[u8] {
+		&self.mmap[self.layout.tiles.clone()]
+	}
+
+	/// Tile data of a single tile, one palette index per pixel.
+	pub fn tile(&self, tile_index: u16) -> Option<&[u8]> {
+		if tile_index >= self.tile_count() {
+			return None;
+		}
+
+		let start = tile_index as usize * TILE_DATA_SIZE;
+		self.tiles().get(start..start + TILE_DATA_SIZE)
+	}
+
+	pub fn palette(&self) -> &[u8] {
+		&self.mmap[self.layout.palette.clone()]
+	}
+
+	pub fn pass_table(&self) -> Result<&[u8], WrlError> {
+		let pass_table = self.layout.pass_table();
+		match self.mmap.get(pass_table.clone()) {
+			Some(bytes) => Ok(bytes),
+			None => Err(WrlError::Truncated {
+				section: WrlSection::PassTable,
+				expected: pass_table.len() as u64,
+				found: self.mmap.len().saturating_sub(pass_table.start) as u64,
+			}),
+		}
+	}
+
+	/// WRL content without the tail header, the part of the file covered by the map hash.
+	pub fn content(&self) -> Result<&[u8], WrlError> {
+		let pass_table = self.pass_table()?;
+		Ok(&self.mmap[..self.layout.palette.end + pass_table.len()])
+	}
+
+	/// Data after the pass table, empty when the file has no tail header or is truncated.
+	pub fn tail(&self) -> &[u8] {
+		self.mmap.get(self.layout.pass_table().end..).unwrap_or(&[])
+	}
+
+	/// Copies the header sections, without the bigmap and tile data.
+	pub fn to_header(&self) -> WRLHeader {
+		WRLHeader {
+			format_version: self.header().version,
+			width: self.width(),
+			height: self.height(),
+			tile_count: self.tile_count(),
+			minimap: self.minimap().to_vec(),
+			palette: self.palette().to_vec(),
+		}
+	}
+
+	/// Copies all sections into an owned WRL file.
+	pub fn to_wrl_file(&self) -> Result<WRLFile, WrlError> {
+		Ok(WRLFile {
+			header: self.header(),
+			width: self.width(),
+			height: self.height(),
+			minimap: self.minimap().to_vec(),
+			bigmap: self.bigmap(),
+			tile_count: self.tile_count(),
+			tiles: self.tiles().to_vec(),
+			palette: self.palette().to_vec(),
+			pass_table: self.pass_table()?.to_vec(),
+		})
+	}
+}
+
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use std::path::PathBuf;
+
+	use crate::run_test;
+	use crate::read_wrl_file;
+
+	#[test]
+	fn test_wrl_view() {
+		run_test!({
+			// Arrange
+			let path = PathBuf::from("test_files/reference/REF.WRL.TAIL");
+			let wrl_file = read_wrl_file(&path).unwrap();
+			let bytes = std::fs::read(&path).unwrap();
+
+			// Act
+			let view = WrlView::open(&path).unwrap();
+
+			// Assert
+			assert_eq!(view.width(), 16);
+			assert_eq!(view.height(), 16);
+			assert_eq!(view.tile_count(), 64);
+			assert_eq!(view.minimap(), wrl_file.minimap);
+			assert_eq!(view.bigmap(), wrl_file.bigmap);
+			assert_eq!(view.cell_tile(1, 0), Some(2));
+			assert_eq!(view.cell_tile(16, 0), None);
+			assert_eq!(view.tile(3), Some(&wrl_file.tiles[3 * TILE_DATA_SIZE..4 * TILE_DATA_SIZE]));
+			assert_eq!(view.tile(64), None);
+			assert_eq!(view.content().unwrap().len(), 263755);
+			assert_eq!(view.tail(), &bytes[263755..]);
+			assert_eq!(view.to_wrl_file(), Ok(wrl_file));
+
+			let logs = get_captured_logs();
+			assert!(logs.is_empty());
+		});
+	}
+
+	#[test]
+	fn test_wrl_view_truncated_pass_table() {
+		run_test!({
+			// Arrange
+			let path = PathBuf::from("test_files/reference/REF.WRL.TRIMMED");
+
+			// Act
+			let view = WrlView::open(&path).unwrap();
+
+			// Assert
+			assert_eq!(view.tiles().len(), view.tile_count() as usize * TILE_DATA_SIZE);
+			assert_eq!(view.tail(), &[] as &[u8]);
+			assert_eq!(view.content().unwrap_err(), WrlError::Truncated {
+				section: WrlSection::PassTable,
+				expected: view.tile_count() as u64,
+				found: view.tile_count() as u64 - 1,
+			});
+
+			let logs = get_captured_logs();
+			assert!(logs.is_empty());
+		});
+	}
+
+	#[test]
+	fn test_wrl_view_truncated_tiles() {
+		run_test!({
+			// Arrange
+			std::fs::create_dir_all("test_files/temp").expect("Failed to create temp directory");
+			let path = PathBuf::from("test_files/temp/REF.WRL.VIEW.TRUNCATED");
+			let bytes = std::fs::read("test_files/reference/REF.WRL").unwrap();
+			std::fs::write(&path, &bytes[..1000]).unwrap();
+
+			// Act
+			let result = WrlView::open(&path);
+
+			// Assert
+			assert_eq!(result.unwrap_err(), WrlError::Truncated {
+				section: WrlSection::Tiles,
+				expected: 64 * TILE_DATA_SIZE as u64,
+				found: 221,
+			});
+
+			// Clean up
+			if let Err(e) = std::fs::remove_file(&path) {
+				log::error!("Failed to remove test file after test: {}", path.display());
+				log::error!("{}", e);
+			}
+
+			let logs = get_captured_logs();
+			assert_eq!(logs.len(), 1);
+			assert_eq!(logs[0], "[ERROR] Failed to read tiles from file: test_files/temp/REF.WRL.VIEW.TRUNCATED");
+		});
+	}
+}
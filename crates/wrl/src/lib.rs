@@ -1,9 +1,21 @@
+pub mod analysis;
+pub use analysis::*;
+
 pub mod consts;
 pub use consts::*;
 
+pub mod error;
+pub use error::*;
+
 pub use file::*;
 pub mod file;
 
+pub mod ops;
+pub use ops::*;
+
+pub mod passability;
+pub use passability::*;
+
 pub mod preview;
 pub use preview::*;
 
@@ -0,0 +1,68 @@
+use crate::consts::TILE_DATA_SIZE;
+
+/// Calculates the average palette color of a tile.
+/// Color values keep the palette scale, so the result can be compared with palette entries.
+pub fn calc_tile_average_color(tiles: &[u8], palette: &[u8], tile_index: usize) -> [u8; 3] {
+	let tile_start = tile_index * TILE_DATA_SIZE;
+	let Some(tile) = tiles.get(tile_start..tile_start + TILE_DATA_SIZE) else {
+		return [0, 0, 0];
+	};
+
+	let mut color = [0u32; 3];
+	let mut pixel_count = 0u32;
+
+	for &color_index in tile {
+		let palette_index = color_index as usize * 3;
+		if palette_index + 2 >= palette.len() {
+			continue;
+		}
+		color[0] += palette[palette_index] as u32;
+		color[1] += palette[palette_index + 1] as u32;
+		color[2] += palette[palette_index + 2] as u32;
+		pixel_count += 1;
+	}
+
+	if pixel_count == 0 {
+		return [0, 0, 0];
+	}
+
+	[
+		(color[0] / pixel_count) as u8,
+		(color[1] / pixel_count) as u8,
+		(color[2] / pixel_count) as u8,
+	]
+}
+
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	use crate::run_test;
+
+	#[test]
+	fn test_calc_tile_average_color() {
+		run_test!({
+			// Arrange
+			let palette = vec![
+				0, 0, 0,
+				200, 100, 50,
+			];
+			let mut tiles = vec![0u8; TILE_DATA_SIZE * 2];
+			tiles[TILE_DATA_SIZE..TILE_DATA_SIZE + TILE_DATA_SIZE / 2].fill(1);
+
+			// Act
+			let first_color = calc_tile_average_color(&tiles, &palette, 0);
+			let second_color = calc_tile_average_color(&tiles, &palette, 1);
+			let missing_color = calc_tile_average_color(&tiles, &palette, 2);
+
+			// Assert
+			assert_eq!(first_color, [0, 0, 0]);
+			assert_eq!(second_color, [100, 50, 25]);
+			assert_eq!(missing_color, [0, 0, 0]);
+
+			let logs = get_captured_logs();
+			assert!(logs.is_empty());
+		});
+	}
+}
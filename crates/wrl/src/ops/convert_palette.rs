@@ -0,0 +1,268 @@
+use rayon::prelude::*;
+
+use crate::consts::{TILE_DATA_SIZE, TILE_SIZE};
+use crate::cycle_palette::{PaletteCycleRange, PLANET_PALETTE_CYCLE_RANGES};
+use crate::error::{WrlError, WrlSection};
+use crate::ops::generate_minimap::generate_minimap;
+use crate::structs::WRLFile;
+
+/// Palette range of the interface and unit colors, which the game expects at fixed indices on every planet.
+pub const PLANET_PALETTE_UI_RANGE: PaletteCycleRange = PaletteCycleRange { start: 0, end: 63 };
+
+/// Floyd-Steinberg weights, in sixteenths, of the neighbors receiving the color error of a pixel.
+const DITHERING_WEIGHTS: [(isize, usize, i32); 4] = [(1, 0, 7), (-1, 1, 3), (0, 1, 5), (1, 1, 1)];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum PaletteDithering {
+	/// Every color is replaced by the closest target color.
+	#[default]
+	None,
+	/// The color error of every pixel is diffused to its neighbors within the tile.
+	FloydSteinberg,
+}
+
+/// Remaps the tiles of the map to the target palette, e.g. to turn a green planet map into a snow one.
+/// The new palette is the target palette with the UI and animation ranges kept from the map palette,
+/// so pixels using those reserved colors keep their index. Other pixels get the closest
+/// non-reserved target color. The minimap is regenerated from the converted tiles.
+pub fn convert_palette(
+	wrl_file: &WRLFile,
+	target_palette: &[u8],
+	dithering: PaletteDithering,
+) -> Result<WRLFile, WrlError> {
+	wrl_file.check_sections_size()?;
+
+	if target_palette.len() != wrl_file.palette.len() {
+		return Err(WrlError::InvalidSectionSize {
+			section: WrlSection::Palette,
+			expected: wrl_file.palette.len() as u64,
+			found: target_palette.len() as u64,
+		});
+	}
+
+	let reserved = reserved_palette_indices();
+	let mut palette = target_palette.to_vec();
+	for (color_index, _) in reserved.iter().enumerate().filter(|(_, reserved)| **reserved) {
+		let palette_index = color_index * 3;
+		palette[palette_index..palette_index + 3]
+			.copy_from_slice(&wrl_file.palette[palette_index..palette_index + 3]);
+	}
+
+	let candidates: Vec<(u8, [i32; 3])> = (0..256)
+		.filter(|&color_index| !reserved[color_index])
+		.map(|color_index| (color_index as u8, palette_color(&palette, color_index)))
+		.collect();
+
+	let tiles: Vec<u8> = match dithering {
+		PaletteDithering::None => {
+			let color_map: Vec<u8> = (0..256)
+				.map(|color_index| {
+					if reserved[color_index] {
+						color_index as u8
+					} else {
+						closest_candidate(&candidates, palette_color(&wrl_file.palette, color_index)).0
+					}
+				})
+				.collect();
+
+			wrl_file.tiles.par_iter().map(|&color_index| color_map[color_index as usize]).collect()
+		}
+		PaletteDithering::FloydSteinberg => wrl_file
+			.tiles
+			.par_chunks_exact(TILE_DATA_SIZE)
+			.flat_map_iter(|tile| dither_tile(tile, &wrl_file.palette, &reserved, &candidates))
+			.collect(),
+	};
+
+	let minimap = generate_minimap(&wrl_file.bigmap, &tiles, &palette);
+
+	Ok(WRLFile {
+		header: wrl_file.header,
+		width: wrl_file.width,
+		height: wrl_file.height,
+		minimap,
+		bigmap: wrl_file.bigmap.clone(),
+		tile_count: wrl_file.tile_count,
+		tiles,
+		palette,
+		pass_table: wrl_file.pass_table.clone(),
+	})
+}
+
+/// Marks the palette indices of the UI range and of the animated water and shoreline ranges.
+pub(crate) fn reserved_palette_indices() -> [bool; 256] {
+	let mut reserved = [false; 256];
+	for range in std::iter::once(&PLANET_PALETTE_UI_RANGE).chain(&PLANET_PALETTE_CYCLE_RANGES) {
+		for color_index in range.start..=range.end {
+			reserved[color_index as usize] = true;
+		}
+	}
+
+	reserved
+}
+
+fn palette_color(palette: &[u8], color_index: usize) -> [i32; 3] {
+	let palette_index = color_index * 3;
+	[
+		palette[palette_index] as i32,
+		palette[palette_index + 1] as i32,
+		palette[palette_index + 2] as i32,
+	]
+}
+
+/// Finds the candidate closest to the color, using squared RGB distance.
+/// Ties are resolved in favor of the lower palette index.
+fn closest_candidate(candidates: &[(u8, [i32; 3])], color: [i32; 3]) -> (u8, [i32; 3]) {
+	let mut closest = candidates[0];
+	let mut closest_distance = i32::MAX;
+
+	for &(color_index, candidate) in candidates {
+		let distance = (0..3).map(|c| (candidate[c] - color[c]).pow(2)).sum();
+		if distance < closest_distance {
+			closest_distance = distance;
+			closest = (color_index, candidate);
+		}
+	}
+
+	closest
+}
+
+/// Converts the tile with Floyd-Steinberg error diffusion. Tiles are dithered on their own,
+/// so the result does not depend on their neighbors and shared tiles stay shared.
+fn dither_tile(
+	tile: &[u8],
+	source_palette: &[u8],
+	reserved: &[bool; 256],
+	candidates: &[(u8, [i32; 3])],
+) -> Vec<u8> {
+	// Diffused errors are kept in sixteenths, so small errors are not lost to rounding.
+	let mut errors = vec![[0i32; 3]; TILE_DATA_SIZE];
+	let mut converted = Vec::with_capacity(TILE_DATA_SIZE);
+
+	for y in 0..TILE_SIZE {
+		for x in 0..TILE_SIZE {
+			let pixel = y * TILE_SIZE + x;
+			let color_index = tile[pixel];
+			if reserved[color_index as usize] {
+				converted.push(color_index);
+				continue;
+			}
+
+			let source_color = palette_color(source_palette, color_index as usize);
+			let color: [i32; 3] = std::array::from_fn(|c| (source_color[c] + errors[pixel][c] / 16).clamp(0, 255));
+			let (target_index, target_color) = closest_candidate(candidates, color);
+			converted.push(target_index);
+
+			for (dx, dy, weight) in DITHERING_WEIGHTS {
+				let (Some(neighbor_x), neighbor_y) = (x.checked_add_signed(dx), y + dy) else {
+					continue;
+				};
+				if neighbor_x >= TILE_SIZE || neighbor_y >= TILE_SIZE {
+					continue;
+				}
+
+				let neighbor_error = &mut errors[neighbor_y * TILE_SIZE + neighbor_x];
+				for c in 0..3 {
+					neighbor_error[c] += (color[c] - target_color[c]) * weight;
+				}
+			}
+		}
+	}
+
+	converted
+}
+
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use std::path::PathBuf;
+
+	use crate::run_test;
+	use crate::read_wrl_file;
+
+	fn read_converted_test_map() -> WRLFile {
+		let mut wrl_file = read_wrl_file(&PathBuf::from("test_files/reference/REF.WRL")).unwrap();
+		// REF.WRL only uses UI colors, tile 2 gets a terrain color. It is used by bigmap cell 1.
+		wrl_file.tiles[2 * TILE_DATA_SIZE..3 * TILE_DATA_SIZE].fill(150);
+		wrl_file
+	}
+
+	#[test]
+	fn test_convert_palette() {
+		run_test!({
+			// Arrange
+			let wrl_file = read_converted_test_map();
+			let mut target_palette = wrl_file.palette.clone();
+			target_palette[0..3].copy_from_slice(&[9, 9, 9]);
+			target_palette[100 * 3..100 * 3 + 3].copy_from_slice(&[0, 0, 255]);
+			target_palette[150 * 3..150 * 3 + 3].copy_from_slice(&[0, 0, 255]);
+
+			// Act
+			let converted = convert_palette(&wrl_file, &target_palette, PaletteDithering::None).unwrap();
+
+			// Assert
+			assert_eq!(converted.palette[0..3], [0, 0, 0]);
+			assert_eq!(converted.palette[100 * 3..100 * 3 + 3], [100, 100, 100]);
+			assert_eq!(converted.palette[150 * 3..150 * 3 + 3], [0, 0, 255]);
+			assert!(converted.tiles[2 * TILE_DATA_SIZE..3 * TILE_DATA_SIZE].iter().all(|&c| c == 149));
+			assert_eq!(converted.tiles[..2 * TILE_DATA_SIZE], wrl_file.tiles[..2 * TILE_DATA_SIZE]);
+			assert_eq!(converted.tiles[3 * TILE_DATA_SIZE..], wrl_file.tiles[3 * TILE_DATA_SIZE..]);
+			assert_eq!(converted.minimap[1], 149);
+			assert_eq!(converted.minimap[2], 4);
+			assert_eq!(converted.bigmap, wrl_file.bigmap);
+			assert_eq!(converted.pass_table, wrl_file.pass_table);
+
+			let logs = get_captured_logs();
+			assert!(logs.is_empty());
+		});
+	}
+
+	#[test]
+	fn test_convert_palette_dithering() {
+		run_test!({
+			// Arrange
+			let wrl_file = read_converted_test_map();
+			let mut target_palette = wrl_file.palette.clone();
+			for color_index in 149..=151 {
+				target_palette[color_index * 3..color_index * 3 + 3].copy_from_slice(&[255, 0, 0]);
+			}
+
+			// Act
+			let converted = convert_palette(&wrl_file, &target_palette, PaletteDithering::None).unwrap();
+			let dithered = convert_palette(&wrl_file, &target_palette, PaletteDithering::FloydSteinberg).unwrap();
+
+			// Assert
+			let tile = 2 * TILE_DATA_SIZE..3 * TILE_DATA_SIZE;
+			assert!(converted.tiles[tile.clone()].iter().all(|&c| c == 148));
+			assert!(dithered.tiles[tile.clone()].iter().all(|&c| c == 148 || c == 152));
+			let average = dithered.tiles[tile].iter().map(|&c| c as f64).sum::<f64>() / TILE_DATA_SIZE as f64;
+			assert!((average - 150.0).abs() < 0.5, "average: {}", average);
+			assert_eq!(dithered.tiles[3 * TILE_DATA_SIZE..], wrl_file.tiles[3 * TILE_DATA_SIZE..]);
+
+			let logs = get_captured_logs();
+			assert!(logs.is_empty());
+		});
+	}
+
+	#[test]
+	fn test_convert_palette_invalid_palette_size() {
+		run_test!({
+			// Arrange
+			let wrl_file = read_converted_test_map();
+
+			// Act
+			let result = convert_palette(&wrl_file, &[0; 10], PaletteDithering::None);
+
+			// Assert
+			assert_eq!(result.unwrap_err(), WrlError::InvalidSectionSize {
+				section: WrlSection::Palette,
+				expected: 256 * 3,
+				found: 10,
+			});
+
+			let logs = get_captured_logs();
+			assert!(logs.is_empty());
+		});
+	}
+}
@@ -0,0 +1,84 @@
+use crate::error::WrlError;
+use crate::ops::resize_map::resize_map;
+use crate::structs::WRLFile;
+
+/// Crops the map to the cell rectangle starting at (x, y).
+pub fn crop_map(
+	wrl_file: &WRLFile,
+	x: u16,
+	y: u16,
+	width: u16,
+	height: u16,
+) -> Result<WRLFile, WrlError> {
+	if width == 0 || height == 0 {
+		return Err(WrlError::InvalidDimensions { width, height });
+	}
+
+	if x as u32 + width as u32 > wrl_file.width as u32 || y as u32 + height as u32 > wrl_file.height as u32 {
+		return Err(WrlError::InvalidRegion { x, y, width, height });
+	}
+
+	resize_map(wrl_file, width, height, -(x as i32), -(y as i32), 0)
+}
+
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use std::path::PathBuf;
+
+	use crate::run_test;
+	use crate::{read_wrl_file, verify_file, write_wrl_file};
+
+	#[test]
+	fn test_crop_map() {
+		run_test!({
+			// Arrange
+			std::fs::create_dir_all("test_files/temp").expect("Failed to create temp directory");
+			let file_path = PathBuf::from("test_files/temp/REF.WRL.CROPPED");
+			let wrl_file = read_wrl_file(&PathBuf::from("test_files/reference/REF.WRL")).unwrap();
+
+			// Act
+			let cropped = crop_map(&wrl_file, 2, 3, 4, 5).unwrap();
+
+			// Assert
+			assert_eq!(cropped.width, 4);
+			assert_eq!(cropped.height, 5);
+			for y in 0..5 {
+				for x in 0..4 {
+					assert_eq!(cropped.bigmap[y * 4 + x], wrl_file.bigmap[(y + 3) * 16 + x + 2]);
+					assert_eq!(cropped.minimap[y * 4 + x], wrl_file.minimap[(y + 3) * 16 + x + 2]);
+				}
+			}
+
+			write_wrl_file(&file_path, &cropped, None).unwrap();
+			assert_eq!(verify_file(&file_path), Ok(true));
+
+			// Clean up
+			if let Err(e) = std::fs::remove_file(&file_path) {
+				log::error!("Failed to remove test file after test: {}", file_path.display());
+				log::error!("{}", e);
+			}
+
+			let logs = get_captured_logs();
+			assert!(logs.is_empty());
+		});
+	}
+
+	#[test]
+	fn test_crop_map_invalid_region() {
+		run_test!({
+			// Arrange
+			let wrl_file = read_wrl_file(&PathBuf::from("test_files/reference/REF.WRL")).unwrap();
+
+			// Act
+			let result = crop_map(&wrl_file, 10, 0, 8, 8);
+
+			// Assert
+			assert_eq!(result, Err(WrlError::InvalidRegion { x: 10, y: 0, width: 8, height: 8 }));
+
+			let logs = get_captured_logs();
+			assert!(logs.is_empty());
+		});
+	}
+}
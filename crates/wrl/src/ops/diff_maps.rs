@@ -0,0 +1,295 @@
+use std::collections::{HashMap, HashSet};
+
+use serde::Serialize;
+
+use crate::consts::TILE_DATA_SIZE;
+use crate::error::WrlError;
+use crate::structs::WRLFile;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct CellChange {
+	pub x: u16,
+	pub y: u16,
+	/// Tile of the cell in the old map, `None` when the cell is outside of the old map.
+	pub old_tile: Option<u16>,
+	/// Tile of the cell in the new map, `None` when the cell is outside of the new map.
+	pub new_tile: Option<u16>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct PaletteChange {
+	pub index: u8,
+	pub old_color: [u8; 3],
+	pub new_color: [u8; 3],
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct PassTableChange {
+	/// Tile in the new map.
+	pub tile_index: u16,
+	/// Tile with the same pixels in the old map, or the same index for tiles edited in place.
+	pub old_tile_index: u16,
+	pub old_value: u8,
+	pub new_value: u8,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct MapDiff {
+	pub old_size: (u16, u16),
+	pub new_size: (u16, u16),
+	pub changed_cells: Vec<CellChange>,
+	pub added_tiles: Vec<u16>,
+	pub removed_tiles: Vec<u16>,
+	pub changed_tiles: Vec<u16>,
+	pub palette_changes: Vec<PaletteChange>,
+	pub pass_table_changes: Vec<PassTableChange>,
+}
+
+impl MapDiff {
+	pub fn is_empty(&self) -> bool {
+		self.old_size == self.new_size
+			&& self.changed_cells.is_empty()
+			&& self.added_tiles.is_empty()
+			&& self.removed_tiles.is_empty()
+			&& self.changed_tiles.is_empty()
+			&& self.palette_changes.is_empty()
+			&& self.pass_table_changes.is_empty()
+	}
+}
+
+/// Compares two maps section by section.
+/// Tiles are matched by their pixels: added and removed tiles hold pixels found only in the new
+/// or only in the old map, so renumbered and duplicated tiles are not reported. A tile index holding
+/// such one-sided pixels in both maps was edited in place and is reported as changed instead.
+/// Pass table values are compared between tiles matched by their pixels, palette entries by index.
+/// Cells are compared by position and by what they show, so a cell only changes
+/// when its tile pixels or pass table value differ, not when the tile is renumbered.
+pub fn diff_maps(old_wrl: &WRLFile, new_wrl: &WRLFile) -> Result<MapDiff, WrlError> {
+	for wrl_file in [old_wrl, new_wrl] {
+		wrl_file.check_sections_size()?;
+		wrl_file.check_tile_indices()?;
+	}
+
+	let max_width = old_wrl.width.max(new_wrl.width);
+	let max_height = old_wrl.height.max(new_wrl.height);
+
+	let mut changed_cells = Vec::new();
+	for y in 0..max_height {
+		for x in 0..max_width {
+			let old_tile = cell_tile(old_wrl, x, y);
+			let new_tile = cell_tile(new_wrl, x, y);
+
+			let is_changed = match (old_tile, new_tile) {
+				(Some(old_tile), Some(new_tile)) => {
+					tile_pixels(old_wrl, old_tile) != tile_pixels(new_wrl, new_tile)
+						|| old_wrl.pass_table[old_tile as usize] != new_wrl.pass_table[new_tile as usize]
+				}
+				(None, None) => false,
+				_ => true,
+			};
+
+			if is_changed {
+				changed_cells.push(CellChange { x, y, old_tile, new_tile });
+			}
+		}
+	}
+
+	let mut old_tiles: HashMap<&[u8], Vec<u16>> = HashMap::new();
+	for tile_index in 0..old_wrl.tile_count {
+		old_tiles.entry(tile_pixels(old_wrl, tile_index)).or_default().push(tile_index);
+	}
+	let new_tiles: HashSet<&[u8]> = (0..new_wrl.tile_count).map(|tile_index| tile_pixels(new_wrl, tile_index)).collect();
+	let is_old_only = |tile_index: u16| !new_tiles.contains(tile_pixels(old_wrl, tile_index));
+	let is_new_only = |tile_index: u16| !old_tiles.contains_key(tile_pixels(new_wrl, tile_index));
+
+	let common_tile_count = old_wrl.tile_count.min(new_wrl.tile_count);
+	let changed_tiles: Vec<u16> = (0..common_tile_count)
+		.filter(|&tile_index| is_old_only(tile_index) && is_new_only(tile_index))
+		.collect();
+	let added_tiles = (0..new_wrl.tile_count)
+		.filter(|&tile_index| is_new_only(tile_index) && !changed_tiles.contains(&tile_index))
+		.collect();
+	let removed_tiles = (0..old_wrl.tile_count)
+		.filter(|&tile_index| is_old_only(tile_index) && !changed_tiles.contains(&tile_index))
+		.collect();
+
+	// A tile keeps its value when any old tile with the same pixels has it.
+	let pass_table_changes = (0..new_wrl.tile_count)
+		.filter_map(|tile_index| {
+			let new_value = new_wrl.pass_table[tile_index as usize];
+			let old_tile_indices = match old_tiles.get(tile_pixels(new_wrl, tile_index)) {
+				Some(old_tile_indices) => old_tile_indices.as_slice(),
+				None if changed_tiles.contains(&tile_index) => std::slice::from_ref(&tile_index),
+				None => return None,
+			};
+			if old_tile_indices.iter().any(|&old_tile_index| old_wrl.pass_table[old_tile_index as usize] == new_value) {
+				return None;
+			}
+
+			let old_tile_index = old_tile_indices[0];
+			Some(PassTableChange {
+				tile_index,
+				old_tile_index,
+				old_value: old_wrl.pass_table[old_tile_index as usize],
+				new_value,
+			})
+		})
+		.collect();
+
+	let palette_changes = old_wrl
+		.palette
+		.chunks_exact(3)
+		.zip(new_wrl.palette.chunks_exact(3))
+		.enumerate()
+		.filter(|(_, (old_color, new_color))| old_color != new_color)
+		.map(|(index, (old_color, new_color))| PaletteChange {
+			index: index as u8,
+			old_color: [old_color[0], old_color[1], old_color[2]],
+			new_color: [new_color[0], new_color[1], new_color[2]],
+		})
+		.collect();
+
+	Ok(MapDiff {
+		old_size: (old_wrl.width, old_wrl.height),
+		new_size: (new_wrl.width, new_wrl.height),
+		changed_cells,
+		added_tiles,
+		removed_tiles,
+		changed_tiles,
+		palette_changes,
+		pass_table_changes,
+	})
+}
+
+fn cell_tile(wrl_file: &WRLFile, x: u16, y: u16) -> Option<u16> {
+	if x >= wrl_file.width || y >= wrl_file.height {
+		return None;
+	}
+
+	Some(wrl_file.bigmap[y as usize * wrl_file.width as usize + x as usize])
+}
+
+fn tile_pixels(wrl_file: &WRLFile, tile_index: u16) -> &[u8] {
+	let tile_start = tile_index as usize * TILE_DATA_SIZE;
+	&wrl_file.tiles[tile_start..tile_start + TILE_DATA_SIZE]
+}
+
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use std::path::PathBuf;
+
+	use crate::run_test;
+	use crate::{optimize_tiles, pad_map, read_wrl_file};
+
+	#[test]
+	fn test_diff_maps_identical() {
+		run_test!({
+			// Arrange
+			let wrl_file = read_wrl_file(&PathBuf::from("test_files/reference/REF.WRL")).unwrap();
+			let mut optimized = wrl_file.clone();
+			optimize_tiles(&mut optimized).unwrap();
+
+			// Act
+			let diff = diff_maps(&wrl_file, &wrl_file).unwrap();
+			let optimized_diff = diff_maps(&wrl_file, &optimized).unwrap();
+
+			// Assert
+			assert!(diff.is_empty());
+			// Renumbered tiles do not change what the cells show, only the unused odd tiles are gone.
+			assert!(optimized_diff.changed_cells.is_empty());
+			assert!(optimized_diff.changed_tiles.is_empty());
+			assert!(optimized_diff.added_tiles.is_empty());
+			assert_eq!(optimized_diff.removed_tiles, (1..64).step_by(2).collect::<Vec<u16>>());
+
+			let logs = get_captured_logs();
+			assert!(logs.is_empty());
+		});
+	}
+
+	#[test]
+	fn test_diff_maps_changes() {
+		run_test!({
+			// Arrange
+			let old_wrl = read_wrl_file(&PathBuf::from("test_files/reference/REF.WRL")).unwrap();
+			let mut new_wrl = pad_map(&old_wrl, 0, 0, 1, 0, 0).unwrap();
+			// Cell (1, 0) uses tile 2, cell (0, 1) uses tile 32.
+			new_wrl.tiles[2 * TILE_DATA_SIZE] = 200;
+			new_wrl.pass_table[32] = 1;
+			new_wrl.palette[3..6].copy_from_slice(&[10, 20, 30]);
+
+			// Act
+			let diff = diff_maps(&old_wrl, &new_wrl).unwrap();
+
+			// Assert
+			assert_eq!(diff.old_size, (16, 16));
+			assert_eq!(diff.new_size, (17, 16));
+			assert_eq!(diff.changed_tiles, vec![2]);
+			assert!(diff.added_tiles.is_empty());
+			assert!(diff.removed_tiles.is_empty());
+			assert_eq!(diff.palette_changes, vec![PaletteChange { index: 1, old_color: [1, 1, 1], new_color: [10, 20, 30] }]);
+			assert_eq!(diff.pass_table_changes, vec![PassTableChange { tile_index: 32, old_tile_index: 32, old_value: 0, new_value: 1 }]);
+			// Tiles 2 and 32 are both used by 8 cells, plus the new column of 16 cells.
+			assert_eq!(diff.changed_cells.len(), 32);
+			assert_eq!(diff.changed_cells[0], CellChange { x: 1, y: 0, old_tile: Some(2), new_tile: Some(2) });
+			assert!(diff.changed_cells.contains(&CellChange { x: 16, y: 3, old_tile: None, new_tile: Some(0) }));
+
+			let logs = get_captured_logs();
+			assert!(logs.is_empty());
+		});
+	}
+
+	#[test]
+	fn test_diff_maps_tiles_matched_by_content() {
+		run_test!({
+			// Arrange
+			let old_wrl = read_wrl_file(&PathBuf::from("test_files/reference/REF.WRL")).unwrap();
+			let mut new_wrl = old_wrl.clone();
+			// Tiles 3 and 4 swap places, tile 7 is edited, and copies of tile 5 and a new tile are appended.
+			let (tile_3, tile_4) = new_wrl.tiles[3 * TILE_DATA_SIZE..5 * TILE_DATA_SIZE].split_at_mut(TILE_DATA_SIZE);
+			tile_3.swap_with_slice(tile_4);
+			new_wrl.tiles[7 * TILE_DATA_SIZE..8 * TILE_DATA_SIZE].fill(201);
+			new_wrl.tiles.extend_from_within(5 * TILE_DATA_SIZE..6 * TILE_DATA_SIZE);
+			new_wrl.tiles.extend(std::iter::repeat_n(200, TILE_DATA_SIZE));
+			new_wrl.pass_table.extend([0, 0]);
+			new_wrl.tile_count += 2;
+
+			// Act
+			let diff = diff_maps(&old_wrl, &new_wrl).unwrap();
+
+			// Assert
+			assert_eq!(diff.changed_tiles, vec![7]);
+			assert_eq!(diff.added_tiles, vec![65]);
+			assert!(diff.removed_tiles.is_empty());
+
+			let logs = get_captured_logs();
+			assert!(logs.is_empty());
+		});
+	}
+
+	#[test]
+	fn test_diff_maps_pass_table_renumbered_tiles() {
+		run_test!({
+			// Arrange
+			let mut old_wrl = read_wrl_file(&PathBuf::from("test_files/reference/REF.WRL")).unwrap();
+			old_wrl.pass_table[2] = 1;
+			let mut optimized = old_wrl.clone();
+			// Optimizing keeps the used even tiles, old tile 2k becomes tile k.
+			optimize_tiles(&mut optimized).unwrap();
+			let mut edited = optimized.clone();
+			edited.pass_table[3] = 2;
+
+			// Act
+			let optimized_diff = diff_maps(&old_wrl, &optimized).unwrap();
+			let diff = diff_maps(&old_wrl, &edited).unwrap();
+
+			// Assert
+			assert!(optimized_diff.pass_table_changes.is_empty());
+			assert_eq!(diff.pass_table_changes, vec![PassTableChange { tile_index: 3, old_tile_index: 6, old_value: 0, new_value: 2 }]);
+
+			let logs = get_captured_logs();
+			assert!(logs.is_empty());
+		});
+	}
+}
@@ -0,0 +1,73 @@
+/// Finds the palette entry closest to the given color, using squared RGB distance.
+/// Ties are resolved in favor of the lower palette index.
+pub fn find_closest_palette_index(palette: &[u8], color: [u8; 3]) -> u8 {
+	find_closest_unreserved_palette_index(palette, color, &[false; 256])
+}
+
+/// Finds the closest palette entry like `find_closest_palette_index`, skipping the reserved indices.
+/// Returns 0 if every palette entry is reserved.
+pub fn find_closest_unreserved_palette_index(palette: &[u8], color: [u8; 3], reserved: &[bool; 256]) -> u8 {
+	let mut closest_index = 0;
+	let mut closest_distance = u32::MAX;
+
+	for (palette_index, entry) in palette.chunks_exact(3).take(256).enumerate() {
+		if reserved[palette_index] {
+			continue;
+		}
+
+		let distance = color_distance(entry, &color);
+		if distance < closest_distance {
+			closest_distance = distance;
+			closest_index = palette_index;
+			if distance == 0 {
+				break;
+			}
+		}
+	}
+
+	closest_index as u8
+}
+
+fn color_distance(a: &[u8], b: &[u8]) -> u32 {
+	a.iter()
+		.zip(b.iter())
+		.map(|(&a, &b)| {
+			let delta = a as i32 - b as i32;
+			(delta * delta) as u32
+		})
+		.sum()
+}
+
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	use crate::run_test;
+
+	#[test]
+	fn test_find_closest_palette_index() {
+		run_test!({
+			// Arrange
+			let palette = vec![
+				0, 0, 0,
+				255, 0, 0,
+				0, 255, 0,
+				0, 0, 255,
+			];
+
+			// Act & Assert
+			assert_eq!(find_closest_palette_index(&palette, [10, 10, 10]), 0);
+			assert_eq!(find_closest_palette_index(&palette, [200, 20, 30]), 1);
+			assert_eq!(find_closest_palette_index(&palette, [0, 0, 255]), 3);
+
+			let mut reserved = [false; 256];
+			reserved[0] = true;
+			assert_eq!(find_closest_unreserved_palette_index(&palette, [10, 10, 10], &reserved), 1);
+			assert_eq!(find_closest_unreserved_palette_index(&palette, [0, 0, 255], &reserved), 3);
+
+			let logs = get_captured_logs();
+			assert!(logs.is_empty());
+		});
+	}
+}
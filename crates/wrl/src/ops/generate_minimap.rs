@@ -0,0 +1,95 @@
+use crate::consts::TILE_DATA_SIZE;
+use crate::ops::calc_tile_average_color::calc_tile_average_color;
+use crate::ops::find_closest_palette_index::find_closest_palette_index;
+
+/// Computes the minimap from the bigmap terrain.
+/// Every cell gets the palette index closest to the average color of its tile.
+/// Cells referencing missing tiles get palette index 0.
+pub fn generate_minimap(bigmap: &[u16], tiles: &[u8], palette: &[u8]) -> Vec<u8> {
+	let tile_count = tiles.len() / TILE_DATA_SIZE;
+	let mut tile_colors: Vec<Option<u8>> = vec![None; tile_count];
+
+	bigmap
+		.iter()
+		.map(|&tile_index| {
+			let tile_index = tile_index as usize;
+			if tile_index >= tile_count {
+				return 0;
+			}
+
+			*tile_colors[tile_index].get_or_insert_with(|| {
+				let color = calc_tile_average_color(tiles, palette, tile_index);
+				find_closest_palette_index(palette, color)
+			})
+		})
+		.collect()
+}
+
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use std::path::PathBuf;
+
+	use crate::run_test;
+	use crate::read_wrl_file;
+
+	#[test]
+	fn test_generate_minimap() {
+		run_test!({
+			// Arrange
+			let path = PathBuf::from("test_files/reference/REF.WRL");
+			let wrl_file = read_wrl_file(&path).unwrap();
+
+			// Act
+			let minimap = generate_minimap(&wrl_file.bigmap, &wrl_file.tiles, &wrl_file.palette);
+
+			// Assert
+			// Every tile of the reference file is filled with its own index,
+			// and palette entry i is (i, i, i).
+			let expected: Vec<u8> = wrl_file.bigmap.iter().map(|&tile_index| tile_index as u8).collect();
+			assert_eq!(minimap, expected);
+
+			let logs = get_captured_logs();
+			assert!(logs.is_empty());
+		});
+	}
+
+	#[test]
+	fn test_generate_minimap_non_square() {
+		run_test!({
+			// Arrange
+			let path = PathBuf::from("test_files/reference/REF_WIDE.WRL");
+			let wrl_file = read_wrl_file(&path).unwrap();
+
+			// Act
+			let minimap = generate_minimap(&wrl_file.bigmap, &wrl_file.tiles, &wrl_file.palette);
+
+			// Assert
+			assert_eq!(minimap.len(), 24 * 12);
+			assert_eq!(minimap, wrl_file.minimap);
+
+			let logs = get_captured_logs();
+			assert!(logs.is_empty());
+		});
+	}
+
+	#[test]
+	fn test_generate_minimap_missing_tile() {
+		run_test!({
+			// Arrange
+			let palette = vec![0, 0, 0, 10, 10, 10];
+			let tiles = vec![1u8; TILE_DATA_SIZE];
+			let bigmap = vec![0, 5, 0];
+
+			// Act
+			let minimap = generate_minimap(&bigmap, &tiles, &palette);
+
+			// Assert
+			assert_eq!(minimap, vec![1, 0, 1]);
+
+			let logs = get_captured_logs();
+			assert!(logs.is_empty());
+		});
+	}
+}
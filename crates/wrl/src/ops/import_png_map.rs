@@ -0,0 +1,235 @@
+use std::collections::HashMap;
+use std::path::Path;
+
+use image::RgbImage;
+
+use crate::consts::{TILE_DATA_SIZE, TILE_SIZE};
+use crate::error::WrlError;
+use crate::ops::convert_palette::reserved_palette_indices;
+use crate::ops::find_closest_palette_index::find_closest_unreserved_palette_index;
+use crate::ops::generate_minimap::generate_minimap;
+use crate::passability::Passability;
+use crate::structs::{WRLFile, WRLFileHeader};
+
+/// Builds a WRL map from a full-resolution image (64x64 pixels per cell).
+/// Pixels are quantized to the palette of the tile library and identical tiles are merged.
+/// The interface and animated palette ranges are left out, so imported terrain never uses them.
+/// Tiles which also exist in the tile library take its pass table value,
+/// all other tiles default to land.
+pub fn build_map_from_image(image: &RgbImage, tile_library: &WRLFile) -> Result<WRLFile, WrlError> {
+	tile_library.check_sections_size()?;
+
+	let (pixel_width, pixel_height) = image.dimensions();
+	let tile_size = TILE_SIZE as u32;
+	if pixel_width == 0 || pixel_height == 0 || pixel_width % tile_size != 0 || pixel_height % tile_size != 0 {
+		return Err(WrlError::Image(format!(
+			"Image size {}x{} is not a multiple of {} pixels", pixel_width, pixel_height, TILE_SIZE
+		)));
+	}
+
+	let cells_x = pixel_width / tile_size;
+	let cells_y = pixel_height / tile_size;
+	if cells_x > u16::MAX as u32 || cells_y > u16::MAX as u32 {
+		return Err(WrlError::Image(format!(
+			"Image size {}x{} exceeds the maximum map size", pixel_width, pixel_height
+		)));
+	}
+	let width = cells_x as u16;
+	let height = cells_y as u16;
+
+	let palette = &tile_library.palette;
+	let reserved = reserved_palette_indices();
+	let library_pass_values: HashMap<&[u8], u8> = tile_library
+		.tiles
+		.chunks_exact(TILE_DATA_SIZE)
+		.zip(&tile_library.pass_table)
+		.rev()
+		.map(|(tile, &pass_value)| (tile, pass_value))
+		.collect();
+
+	let mut color_indices: HashMap<[u8; 3], u8> = HashMap::new();
+	let mut tile_indices: HashMap<Vec<u8>, u16> = HashMap::new();
+	let mut tiles: Vec<u8> = Vec::new();
+	let mut pass_table: Vec<u8> = Vec::new();
+	let mut bigmap: Vec<u16> = Vec::with_capacity(width as usize * height as usize);
+
+	for cell_y in 0..cells_y {
+		for cell_x in 0..cells_x {
+			let mut tile = Vec::with_capacity(TILE_DATA_SIZE);
+			for tile_y in 0..tile_size {
+				for tile_x in 0..tile_size {
+					let color = image.get_pixel(cell_x * tile_size + tile_x, cell_y * tile_size + tile_y).0;
+					let color_index = *color_indices
+						.entry(color)
+						.or_insert_with(|| find_closest_unreserved_palette_index(palette, color, &reserved));
+					tile.push(color_index);
+				}
+			}
+
+			let tile_index = match tile_indices.get(&tile) {
+				Some(&tile_index) => tile_index,
+				None => {
+					let new_tile_count = pass_table.len() + 1;
+					if new_tile_count > u16::MAX as usize {
+						return Err(WrlError::TooManyTiles { tile_count: new_tile_count });
+					}
+
+					let tile_index = pass_table.len() as u16;
+					let pass_value = library_pass_values
+						.get(tile.as_slice())
+						.copied()
+						.unwrap_or(Passability::Land.as_u8());
+					pass_table.push(pass_value);
+					tiles.extend_from_slice(&tile);
+					tile_indices.insert(tile, tile_index);
+					tile_index
+				}
+			};
+			bigmap.push(tile_index);
+		}
+	}
+
+	let minimap = generate_minimap(&bigmap, &tiles, palette);
+
+	Ok(WRLFile {
+		header: WRLFileHeader::default(),
+		width,
+		height,
+		minimap,
+		bigmap,
+		tile_count: pass_table.len() as u16,
+		tiles,
+		palette: palette.clone(),
+		pass_table,
+	})
+}
+
+/// Reads a PNG image and builds a WRL map from it, see `build_map_from_image`.
+pub fn import_png_map(png_file: &Path, tile_library: &WRLFile) -> Result<WRLFile, WrlError> {
+	let image = match image::open(png_file) {
+		Ok(image) => image.to_rgb8(),
+		Err(e) => {
+			log::error!("Failed to open image file: {}: {}", png_file.display(), e);
+			return Err(WrlError::Image(e.to_string()));
+		}
+	};
+
+	build_map_from_image(&image, tile_library).inspect_err(|e| {
+		log::error!("Failed to import map from image: {}: {}", png_file.display(), e);
+	})
+}
+
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use std::path::PathBuf;
+
+	use crate::run_test;
+	use crate::{read_wrl_file, verify_file, write_full_map_png, write_wrl_file};
+
+	#[test]
+	fn test_build_map_from_image() {
+		run_test!({
+			// Arrange
+			// The reference tiles use the reserved interface colors, moving them to 128..191 keeps them importable.
+			let mut tile_library = read_wrl_file(&PathBuf::from("test_files/reference/REF.WRL")).unwrap();
+			tile_library.tiles.iter_mut().for_each(|color_index| *color_index += 128);
+			tile_library.pass_table[2] = Passability::Water.as_u8();
+			let mut png_data = Vec::new();
+			write_full_map_png(&tile_library, &mut png_data).unwrap();
+			let image = image::load_from_memory(&png_data).unwrap().to_rgb8();
+
+			// Act
+			let wrl_file = build_map_from_image(&image, &tile_library).unwrap();
+
+			// Assert
+			// Only the 32 even tiles of the reference map are used, in first-use order.
+			assert_eq!((wrl_file.width, wrl_file.height), (16, 16));
+			assert_eq!(wrl_file.tile_count, 32);
+			assert_eq!(wrl_file.bigmap[0..4], [0, 1, 2, 3]);
+			assert_eq!(wrl_file.bigmap[32], 0);
+			assert_eq!(wrl_file.tiles[TILE_DATA_SIZE..TILE_DATA_SIZE * 2], tile_library.tiles[TILE_DATA_SIZE * 2..TILE_DATA_SIZE * 3]);
+			assert_eq!(wrl_file.pass_table[0..3], [0, 1, 0]);
+			assert_eq!(wrl_file.minimap[1], 130);
+			assert!(wrl_file.check_sections_size().is_ok());
+
+			let logs = get_captured_logs();
+			assert!(logs.is_empty());
+		});
+	}
+
+	#[test]
+	fn test_build_map_from_image_skips_reserved_colors() {
+		run_test!({
+			// Arrange
+			let tile_library = read_wrl_file(&PathBuf::from("test_files/reference/REF.WRL")).unwrap();
+			let mut png_data = Vec::new();
+			write_full_map_png(&tile_library, &mut png_data).unwrap();
+			let image = image::load_from_memory(&png_data).unwrap().to_rgb8();
+			let reserved = reserved_palette_indices();
+
+			// Act
+			let wrl_file = build_map_from_image(&image, &tile_library).unwrap();
+
+			// Assert
+			// The reference tiles only use interface colors, which all fall back to the closest free index.
+			assert!(wrl_file.tiles.iter().all(|&color_index| !reserved[color_index as usize]));
+			assert_eq!(wrl_file.tile_count, 1);
+			assert_eq!(wrl_file.tiles[0], 64);
+
+			let logs = get_captured_logs();
+			assert!(logs.is_empty());
+		});
+	}
+
+	#[test]
+	fn test_build_map_from_image_invalid_size() {
+		run_test!({
+			// Arrange
+			let tile_library = read_wrl_file(&PathBuf::from("test_files/reference/REF.WRL")).unwrap();
+			let image = RgbImage::new(100, 64);
+
+			// Act
+			let result = build_map_from_image(&image, &tile_library);
+
+			// Assert
+			assert_eq!(result.unwrap_err(), WrlError::Image("Image size 100x64 is not a multiple of 64 pixels".to_string()));
+
+			let logs = get_captured_logs();
+			assert!(logs.is_empty());
+		});
+	}
+
+	#[test]
+	fn test_import_png_map_passes_verification() {
+		run_test!({
+			// Arrange
+			std::fs::create_dir_all("test_files/temp").expect("Failed to create temp directory");
+			let tile_library = read_wrl_file(&PathBuf::from("test_files/reference/REF.WRL")).unwrap();
+			let png_file = PathBuf::from("test_files/temp/IMPORT.PNG");
+			let wrl_output_file = PathBuf::from("test_files/temp/IMPORT.WRL");
+			let mut png_data = Vec::new();
+			write_full_map_png(&tile_library, &mut png_data).unwrap();
+			std::fs::write(&png_file, &png_data).unwrap();
+
+			// Act
+			let wrl_file = import_png_map(&png_file, &tile_library).unwrap();
+
+			// Assert
+			write_wrl_file(&wrl_output_file, &wrl_file, None).unwrap();
+			assert_eq!(verify_file(&wrl_output_file), Ok(true));
+
+			// Clean up
+			for file_path in [&png_file, &wrl_output_file] {
+				if let Err(e) = std::fs::remove_file(file_path) {
+					log::error!("Failed to remove test file after test: {}", file_path.display());
+					log::error!("{}", e);
+				}
+			}
+
+			let logs = get_captured_logs();
+			assert!(logs.is_empty());
+		});
+	}
+}
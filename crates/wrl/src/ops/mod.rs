@@ -0,0 +1,35 @@
+pub mod calc_tile_average_color;
+pub use calc_tile_average_color::*;
+
+pub mod convert_palette;
+pub use convert_palette::*;
+
+pub mod crop_map;
+pub use crop_map::*;
+
+pub mod diff_maps;
+pub use diff_maps::*;
+
+pub mod find_closest_palette_index;
+pub use find_closest_palette_index::*;
+
+pub mod generate_minimap;
+pub use generate_minimap::*;
+
+pub mod import_png_map;
+pub use import_png_map::*;
+
+pub mod optimize_tiles;
+pub use optimize_tiles::*;
+
+pub mod pad_map;
+pub use pad_map::*;
+
+pub mod resize_map;
+pub use resize_map::*;
+
+pub mod transform_map;
+pub use transform_map::*;
+
+pub mod validate_minimap;
+pub use validate_minimap::*;
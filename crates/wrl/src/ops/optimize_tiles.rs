@@ -0,0 +1,177 @@
+use std::collections::HashMap;
+
+use serde::Serialize;
+
+use crate::consts::TILE_DATA_SIZE;
+use crate::error::WrlError;
+use crate::structs::WRLFile;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct OptimizeTilesReport {
+	pub original_tile_count: u16,
+	pub tile_count: u16,
+	pub unused_tiles_removed: usize,
+	pub duplicate_tiles_removed: usize,
+	pub bytes_saved: u64,
+}
+
+/// Removes tiles which are not referenced by the bigmap and merges duplicate tiles.
+/// Tiles are duplicates only when both pixels and pass table values are identical,
+/// so the map plays the same after the optimization.
+/// The remaining tiles keep their relative order.
+pub fn optimize_tiles(wrl_file: &mut WRLFile) -> Result<OptimizeTilesReport, WrlError> {
+	wrl_file.check_sections_size()?;
+	wrl_file.check_tile_indices()?;
+
+	let original_tile_count = wrl_file.tile_count;
+
+	let mut is_tile_used = vec![false; original_tile_count as usize];
+	for &tile_index in &wrl_file.bigmap {
+		is_tile_used[tile_index as usize] = true;
+	}
+
+	let mut index_map: Vec<u16> = vec![0; original_tile_count as usize];
+	let mut unique_tiles: HashMap<(u8, &[u8]), u16> = HashMap::new();
+	let mut kept_tiles: Vec<usize> = Vec::new();
+	let mut unused_tiles_removed = 0;
+	let mut duplicate_tiles_removed = 0;
+
+	for (tile_index, tile) in wrl_file.tiles.chunks_exact(TILE_DATA_SIZE).enumerate() {
+		if !is_tile_used[tile_index] {
+			unused_tiles_removed += 1;
+			continue;
+		}
+
+		let key = (wrl_file.pass_table[tile_index], tile);
+		match unique_tiles.get(&key) {
+			Some(&new_index) => {
+				index_map[tile_index] = new_index;
+				duplicate_tiles_removed += 1;
+			}
+			None => {
+				let new_index = kept_tiles.len() as u16;
+				unique_tiles.insert(key, new_index);
+				index_map[tile_index] = new_index;
+				kept_tiles.push(tile_index);
+			}
+		}
+	}
+
+	let mut tiles = Vec::with_capacity(kept_tiles.len() * TILE_DATA_SIZE);
+	let mut pass_table = Vec::with_capacity(kept_tiles.len());
+	for &tile_index in &kept_tiles {
+		tiles.extend_from_slice(&wrl_file.tiles[tile_index * TILE_DATA_SIZE..(tile_index + 1) * TILE_DATA_SIZE]);
+		pass_table.push(wrl_file.pass_table[tile_index]);
+	}
+
+	for tile_index in wrl_file.bigmap.iter_mut() {
+		*tile_index = index_map[*tile_index as usize];
+	}
+
+	let tile_count = kept_tiles.len() as u16;
+	wrl_file.tiles = tiles;
+	wrl_file.pass_table = pass_table;
+	wrl_file.tile_count = tile_count;
+
+	let removed_tiles = (original_tile_count - tile_count) as u64;
+
+	Ok(OptimizeTilesReport {
+		original_tile_count,
+		tile_count,
+		unused_tiles_removed,
+		duplicate_tiles_removed,
+		bytes_saved: removed_tiles * (TILE_DATA_SIZE as u64 + 1),
+	})
+}
+
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use std::path::PathBuf;
+
+	use crate::run_test;
+	use crate::{read_wrl_file, verify_file, write_wrl_file};
+
+	#[test]
+	fn test_optimize_tiles_unused() {
+		run_test!({
+			// Arrange
+			std::fs::create_dir_all("test_files/temp").expect("Failed to create temp directory");
+			let file_path = PathBuf::from("test_files/temp/REF.WRL.OPTIMIZED");
+			let mut wrl_file = read_wrl_file(&PathBuf::from("test_files/reference/REF.WRL")).unwrap();
+
+			// Act
+			let report = optimize_tiles(&mut wrl_file).unwrap();
+
+			// Assert
+			assert_eq!(report, OptimizeTilesReport {
+				original_tile_count: 64,
+				tile_count: 32,
+				unused_tiles_removed: 32,
+				duplicate_tiles_removed: 0,
+				bytes_saved: 32 * (TILE_DATA_SIZE as u64 + 1),
+			});
+			assert_eq!(wrl_file.tiles.len(), 32 * TILE_DATA_SIZE);
+			assert_eq!(wrl_file.pass_table.len(), 32);
+			assert_eq!(wrl_file.bigmap[0..4], [0, 1, 2, 3]);
+			assert_eq!(wrl_file.tiles[TILE_DATA_SIZE], 2);
+
+			write_wrl_file(&file_path, &wrl_file, None).unwrap();
+			assert_eq!(verify_file(&file_path), Ok(true));
+
+			// Clean up
+			if let Err(e) = std::fs::remove_file(&file_path) {
+				log::error!("Failed to remove test file after test: {}", file_path.display());
+				log::error!("{}", e);
+			}
+
+			let logs = get_captured_logs();
+			assert!(logs.is_empty());
+		});
+	}
+
+	#[test]
+	fn test_optimize_tiles_duplicates() {
+		run_test!({
+			// Arrange
+			let mut wrl_file = read_wrl_file(&PathBuf::from("test_files/reference/REF.WRL")).unwrap();
+			// Tile 2 becomes a pixel copy of tile 0, tile 6 a pixel copy of tile 4 with other passability.
+			wrl_file.tiles.copy_within(0..TILE_DATA_SIZE, 2 * TILE_DATA_SIZE);
+			wrl_file.tiles.copy_within(4 * TILE_DATA_SIZE..5 * TILE_DATA_SIZE, 6 * TILE_DATA_SIZE);
+			wrl_file.pass_table[6] = 1;
+
+			// Act
+			let report = optimize_tiles(&mut wrl_file).unwrap();
+
+			// Assert
+			assert_eq!(report.unused_tiles_removed, 32);
+			assert_eq!(report.duplicate_tiles_removed, 1);
+			assert_eq!(report.tile_count, 31);
+			assert_eq!(wrl_file.bigmap[0..5], [0, 0, 1, 2, 3]);
+			assert_eq!(wrl_file.pass_table[0..4], [0, 0, 1, 0]);
+
+			let logs = get_captured_logs();
+			assert!(logs.is_empty());
+		});
+	}
+
+	#[test]
+	fn test_optimize_tiles_invalid_tile_index() {
+		run_test!({
+			// Arrange
+			let mut wrl_file = read_wrl_file(&PathBuf::from("test_files/reference/REF.WRL")).unwrap();
+			wrl_file.bigmap[3] = 64;
+
+			// Act
+			let result = optimize_tiles(&mut wrl_file);
+
+			// Assert
+			assert_eq!(result, Err(WrlError::InvalidTileIndex { cell: 3, tile_index: 64, tile_count: 64 }));
+			assert_eq!(wrl_file.tile_count, 64);
+
+			let logs = get_captured_logs();
+			assert!(logs.is_empty());
+		});
+	}
+}
@@ -0,0 +1,70 @@
+use crate::error::WrlError;
+use crate::ops::resize_map::resize_map;
+use crate::structs::WRLFile;
+
+/// Grows the map by the given number of cells on each side, filling new cells with `fill_tile`.
+pub fn pad_map(
+	wrl_file: &WRLFile,
+	left: u16,
+	top: u16,
+	right: u16,
+	bottom: u16,
+	fill_tile: u16,
+) -> Result<WRLFile, WrlError> {
+	let width = wrl_file.width as u32 + left as u32 + right as u32;
+	let height = wrl_file.height as u32 + top as u32 + bottom as u32;
+
+	if width > u16::MAX as u32 || height > u16::MAX as u32 {
+		return Err(WrlError::InvalidDimensions {
+			width: width.min(u16::MAX as u32) as u16,
+			height: height.min(u16::MAX as u32) as u16,
+		});
+	}
+
+	resize_map(wrl_file, width as u16, height as u16, left as i32, top as i32, fill_tile)
+}
+
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use std::path::PathBuf;
+
+	use crate::run_test;
+	use crate::{read_wrl_file, verify_file, write_wrl_file};
+
+	#[test]
+	fn test_pad_map() {
+		run_test!({
+			// Arrange
+			std::fs::create_dir_all("test_files/temp").expect("Failed to create temp directory");
+			let file_path = PathBuf::from("test_files/temp/REF.WRL.PADDED");
+			let wrl_file = read_wrl_file(&PathBuf::from("test_files/reference/REF.WRL")).unwrap();
+
+			// Act
+			let padded = pad_map(&wrl_file, 1, 2, 3, 4, 5).unwrap();
+
+			// Assert
+			assert_eq!(padded.width, 20);
+			assert_eq!(padded.height, 22);
+			assert_eq!(padded.bigmap[0], 5);
+			assert_eq!(padded.minimap[0], 5);
+			assert_eq!(padded.bigmap[2 * 20 + 1], wrl_file.bigmap[0]);
+			assert_eq!(padded.bigmap[17 * 20 + 16], wrl_file.bigmap[255]);
+			assert_eq!(padded.bigmap[17 * 20 + 17], 5);
+			assert_eq!(padded.bigmap[21 * 20 + 19], 5);
+
+			write_wrl_file(&file_path, &padded, None).unwrap();
+			assert_eq!(verify_file(&file_path), Ok(true));
+
+			// Clean up
+			if let Err(e) = std::fs::remove_file(&file_path) {
+				log::error!("Failed to remove test file after test: {}", file_path.display());
+				log::error!("{}", e);
+			}
+
+			let logs = get_captured_logs();
+			assert!(logs.is_empty());
+		});
+	}
+}
@@ -0,0 +1,126 @@
+use crate::error::WrlError;
+use crate::ops::calc_tile_average_color::calc_tile_average_color;
+use crate::ops::find_closest_palette_index::find_closest_palette_index;
+use crate::structs::WRLFile;
+
+/// Resizes the map to `width` x `height` cells.
+/// Source cell (x, y) is moved to (x + offset_x, y + offset_y); cells moved outside are dropped,
+/// and new cells are filled with `fill_tile`. Minimap cells of filled cells get the palette
+/// color closest to the average color of the fill tile.
+/// Tiles are kept as they are, use `optimize_tiles` to drop tiles no longer referenced.
+pub fn resize_map(
+	wrl_file: &WRLFile,
+	width: u16,
+	height: u16,
+	offset_x: i32,
+	offset_y: i32,
+	fill_tile: u16,
+) -> Result<WRLFile, WrlError> {
+	wrl_file.check_sections_size()?;
+
+	if width == 0 || height == 0 {
+		return Err(WrlError::InvalidDimensions { width, height });
+	}
+
+	let source_width = wrl_file.width as i64;
+	let source_height = wrl_file.height as i64;
+	let covers_whole_map = offset_x <= 0
+		&& offset_y <= 0
+		&& source_width + offset_x as i64 >= width as i64
+		&& source_height + offset_y as i64 >= height as i64;
+
+	let mut fill_minimap_color = 0;
+	if !covers_whole_map {
+		if fill_tile >= wrl_file.tile_count {
+			return Err(WrlError::TileNotFound { tile_index: fill_tile, tile_count: wrl_file.tile_count });
+		}
+		let fill_color = calc_tile_average_color(&wrl_file.tiles, &wrl_file.palette, fill_tile as usize);
+		fill_minimap_color = find_closest_palette_index(&wrl_file.palette, fill_color);
+	}
+
+	let cell_count = width as usize * height as usize;
+	let mut minimap = vec![fill_minimap_color; cell_count];
+	let mut bigmap = vec![fill_tile; cell_count];
+
+	for y in 0..height as i64 {
+		let source_y = y - offset_y as i64;
+		if source_y < 0 || source_y >= source_height {
+			continue;
+		}
+		for x in 0..width as i64 {
+			let source_x = x - offset_x as i64;
+			if source_x < 0 || source_x >= source_width {
+				continue;
+			}
+			let source_cell = (source_y * source_width + source_x) as usize;
+			let cell = (y * width as i64 + x) as usize;
+			minimap[cell] = wrl_file.minimap[source_cell];
+			bigmap[cell] = wrl_file.bigmap[source_cell];
+		}
+	}
+
+	Ok(WRLFile {
+		header: wrl_file.header,
+		width,
+		height,
+		minimap,
+		bigmap,
+		tile_count: wrl_file.tile_count,
+		tiles: wrl_file.tiles.clone(),
+		palette: wrl_file.palette.clone(),
+		pass_table: wrl_file.pass_table.clone(),
+	})
+}
+
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use std::path::PathBuf;
+
+	use crate::run_test;
+	use crate::read_wrl_file;
+
+	#[test]
+	fn test_resize_map_shift() {
+		run_test!({
+			// Arrange
+			let wrl_file = read_wrl_file(&PathBuf::from("test_files/reference/REF.WRL")).unwrap();
+
+			// Act
+			let resized = resize_map(&wrl_file, 20, 10, 2, -3, 1).unwrap();
+
+			// Assert
+			assert_eq!(resized.width, 20);
+			assert_eq!(resized.height, 10);
+			assert_eq!(resized.bigmap.len(), 200);
+			assert_eq!(resized.minimap.len(), 200);
+			assert_eq!(resized.bigmap[0], 1);
+			assert_eq!(resized.bigmap[2], wrl_file.bigmap[3 * 16]);
+			assert_eq!(resized.minimap[2], wrl_file.minimap[3 * 16]);
+			assert_eq!(resized.bigmap[19], 1);
+			assert_eq!(resized.minimap[19], 1);
+			assert_eq!(resized.tiles, wrl_file.tiles);
+
+			let logs = get_captured_logs();
+			assert!(logs.is_empty());
+		});
+	}
+
+	#[test]
+	fn test_resize_map_invalid_fill_tile() {
+		run_test!({
+			// Arrange
+			let wrl_file = read_wrl_file(&PathBuf::from("test_files/reference/REF.WRL")).unwrap();
+
+			// Act
+			let result = resize_map(&wrl_file, 20, 20, 0, 0, 64);
+
+			// Assert
+			assert_eq!(result, Err(WrlError::TileNotFound { tile_index: 64, tile_count: 64 }));
+
+			let logs = get_captured_logs();
+			assert!(logs.is_empty());
+		});
+	}
+}
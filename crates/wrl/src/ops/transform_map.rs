@@ -0,0 +1,254 @@
+use std::collections::HashMap;
+
+use crate::consts::{TILE_DATA_SIZE, TILE_SIZE};
+use crate::error::WrlError;
+use crate::structs::WRLFile;
+
+/// Rotations are clockwise. Mirroring is done across the vertical axis (horizontal)
+/// or the horizontal axis (vertical).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MapTransform {
+	Rotate90,
+	Rotate180,
+	Rotate270,
+	MirrorHorizontal,
+	MirrorVertical,
+}
+
+impl MapTransform {
+	/// Returns the size of a `width` x `height` grid after the transform.
+	pub fn transform_size(&self, width: usize, height: usize) -> (usize, usize) {
+		match self {
+			MapTransform::Rotate90 | MapTransform::Rotate270 => (height, width),
+			_ => (width, height),
+		}
+	}
+
+	/// Returns the position of the (x, y) point of a `width` x `height` grid after the transform.
+	pub fn transform_point(&self, x: usize, y: usize, width: usize, height: usize) -> (usize, usize) {
+		match self {
+			MapTransform::Rotate90 => (height - 1 - y, x),
+			MapTransform::Rotate180 => (width - 1 - x, height - 1 - y),
+			MapTransform::Rotate270 => (y, width - 1 - x),
+			MapTransform::MirrorHorizontal => (width - 1 - x, y),
+			MapTransform::MirrorVertical => (x, height - 1 - y),
+		}
+	}
+}
+
+/// Rotates or mirrors the map, including the pixels of every tile used by the bigmap.
+/// Transformed tiles reuse an existing tile with the same pixels and pass table value,
+/// otherwise they are appended to the tile set. Original tiles are kept,
+/// use `optimize_tiles` to drop tiles no longer referenced.
+pub fn transform_map(wrl_file: &WRLFile, transform: MapTransform) -> Result<WRLFile, WrlError> {
+	wrl_file.check_sections_size()?;
+	wrl_file.check_tile_indices()?;
+
+	let width = wrl_file.width as usize;
+	let height = wrl_file.height as usize;
+	let (new_width, new_height) = transform.transform_size(width, height);
+
+	let mut tiles = wrl_file.tiles.clone();
+	let mut pass_table = wrl_file.pass_table.clone();
+
+	let mut tile_lookup: HashMap<(u8, Vec<u8>), u16> = HashMap::new();
+	for (tile_index, tile) in wrl_file.tiles.chunks_exact(TILE_DATA_SIZE).enumerate().rev() {
+		tile_lookup.insert((pass_table[tile_index], tile.to_vec()), tile_index as u16);
+	}
+
+	let mut transformed_tiles: HashMap<u16, u16> = HashMap::new();
+	let mut minimap = vec![0u8; new_width * new_height];
+	let mut bigmap = vec![0u16; new_width * new_height];
+
+	for y in 0..height {
+		for x in 0..width {
+			let source_cell = y * width + x;
+			let (new_x, new_y) = transform.transform_point(x, y, width, height);
+			let cell = new_y * new_width + new_x;
+
+			let tile_index = wrl_file.bigmap[source_cell];
+			let new_tile_index = match transformed_tiles.get(&tile_index) {
+				Some(&new_tile_index) => new_tile_index,
+				None => {
+					let tile_start = tile_index as usize * TILE_DATA_SIZE;
+					let tile = transform_tile(&wrl_file.tiles[tile_start..tile_start + TILE_DATA_SIZE], transform);
+					let pass_value = wrl_file.pass_table[tile_index as usize];
+
+					let new_tile_index = match tile_lookup.get(&(pass_value, tile.clone())) {
+						Some(&existing_tile_index) => existing_tile_index,
+						None => {
+							let tile_count = pass_table.len();
+							if tile_count >= u16::MAX as usize {
+								return Err(WrlError::TooManyTiles { tile_count: tile_count + 1 });
+							}
+							tiles.extend_from_slice(&tile);
+							pass_table.push(pass_value);
+							tile_lookup.insert((pass_value, tile), tile_count as u16);
+							tile_count as u16
+						}
+					};
+
+					transformed_tiles.insert(tile_index, new_tile_index);
+					new_tile_index
+				}
+			};
+
+			minimap[cell] = wrl_file.minimap[source_cell];
+			bigmap[cell] = new_tile_index;
+		}
+	}
+
+	Ok(WRLFile {
+		header: wrl_file.header,
+		width: new_width as u16,
+		height: new_height as u16,
+		minimap,
+		bigmap,
+		tile_count: pass_table.len() as u16,
+		tiles,
+		palette: wrl_file.palette.clone(),
+		pass_table,
+	})
+}
+
+/// Transforms the pixels of a single tile.
+fn transform_tile(tile: &[u8], transform: MapTransform) -> Vec<u8> {
+	let mut transformed = vec![0u8; TILE_DATA_SIZE];
+
+	for y in 0..TILE_SIZE {
+		for x in 0..TILE_SIZE {
+			let (new_x, new_y) = transform.transform_point(x, y, TILE_SIZE, TILE_SIZE);
+			transformed[new_y * TILE_SIZE + new_x] = tile[y * TILE_SIZE + x];
+		}
+	}
+
+	transformed
+}
+
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use std::path::PathBuf;
+
+	use crate::run_test;
+	use crate::{read_wrl_file, resize_map, verify_file, write_wrl_file};
+
+	fn read_reference_with_gradient_tile() -> WRLFile {
+		let mut wrl_file = read_wrl_file(&PathBuf::from("test_files/reference/REF.WRL")).unwrap();
+		// Tile 0 gets a horizontal gradient, so rotations and mirrors change its pixels.
+		for y in 0..TILE_SIZE {
+			for x in 0..TILE_SIZE {
+				wrl_file.tiles[y * TILE_SIZE + x] = x as u8;
+			}
+		}
+		wrl_file
+	}
+
+	#[test]
+	fn test_transform_map_rotate_90() {
+		run_test!({
+			// Arrange
+			let wrl_file = read_reference_with_gradient_tile();
+
+			// Act
+			let rotated = transform_map(&wrl_file, MapTransform::Rotate90).unwrap();
+
+			// Assert
+			assert_eq!(rotated.tile_count, 65);
+			assert_eq!(rotated.pass_table.len(), 65);
+			// Source (0, 0) moves to the top-right corner and references the rotated tile.
+			assert_eq!(rotated.bigmap[15], 64);
+			assert_eq!(rotated.minimap[15], wrl_file.minimap[0]);
+			// Source (1, 0) moves to (15, 1).
+			assert_eq!(rotated.bigmap[16 + 15], wrl_file.bigmap[1]);
+			// The gradient is now vertical.
+			let rotated_tile = &rotated.tiles[64 * TILE_DATA_SIZE..];
+			assert_eq!(rotated_tile[0], 0);
+			assert_eq!(rotated_tile[TILE_SIZE - 1], 0);
+			assert_eq!(rotated_tile[TILE_SIZE], 1);
+			assert_eq!(rotated_tile[(TILE_SIZE - 1) * TILE_SIZE], 63);
+
+			let logs = get_captured_logs();
+			assert!(logs.is_empty());
+		});
+	}
+
+	#[test]
+	fn test_transform_map_full_rotation() {
+		run_test!({
+			// Arrange
+			let wrl_file = read_reference_with_gradient_tile();
+
+			// Act
+			let mut rotated = wrl_file.clone();
+			for _ in 0..4 {
+				rotated = transform_map(&rotated, MapTransform::Rotate90).unwrap();
+			}
+
+			// Assert
+			assert_eq!(rotated.bigmap, wrl_file.bigmap);
+			assert_eq!(rotated.minimap, wrl_file.minimap);
+			assert_eq!(rotated.tile_count, 67);
+
+			let logs = get_captured_logs();
+			assert!(logs.is_empty());
+		});
+	}
+
+	#[test]
+	fn test_transform_map_mirror_twice() {
+		run_test!({
+			// Arrange
+			let wrl_file = read_reference_with_gradient_tile();
+
+			// Act
+			let mirrored = transform_map(&wrl_file, MapTransform::MirrorHorizontal).unwrap();
+			let restored = transform_map(&mirrored, MapTransform::MirrorHorizontal).unwrap();
+
+			// Assert
+			assert_eq!(mirrored.bigmap[15], 64);
+			assert_eq!(mirrored.tiles[64 * TILE_DATA_SIZE], 63);
+			assert_eq!(restored.bigmap, wrl_file.bigmap);
+			assert_eq!(restored.tile_count, 65);
+
+			let vertical = transform_map(&wrl_file, MapTransform::MirrorVertical).unwrap();
+			assert_eq!(vertical.bigmap[15 * 16], 0);
+			assert_eq!(vertical.tile_count, 64);
+
+			let logs = get_captured_logs();
+			assert!(logs.is_empty());
+		});
+	}
+
+	#[test]
+	fn test_transform_map_non_square() {
+		run_test!({
+			// Arrange
+			std::fs::create_dir_all("test_files/temp").expect("Failed to create temp directory");
+			let file_path = PathBuf::from("test_files/temp/REF.WRL.ROTATED");
+			let wrl_file = read_wrl_file(&PathBuf::from("test_files/reference/REF.WRL")).unwrap();
+			let wide = resize_map(&wrl_file, 16, 8, 0, 0, 0).unwrap();
+
+			// Act
+			let rotated = transform_map(&wide, MapTransform::Rotate270).unwrap();
+
+			// Assert
+			assert_eq!((rotated.width, rotated.height), (8, 16));
+			// Source (15, 0) moves to (0, 0).
+			assert_eq!(rotated.bigmap[0], wide.bigmap[15]);
+
+			write_wrl_file(&file_path, &rotated, None).unwrap();
+			assert_eq!(verify_file(&file_path), Ok(true));
+
+			// Clean up
+			if let Err(e) = std::fs::remove_file(&file_path) {
+				log::error!("Failed to remove test file after test: {}", file_path.display());
+				log::error!("{}", e);
+			}
+
+			let logs = get_captured_logs();
+			assert!(logs.is_empty());
+		});
+	}
+}
@@ -0,0 +1,167 @@
+use serde::Serialize;
+
+use crate::error::WrlError;
+use crate::ops::generate_minimap::generate_minimap;
+use crate::structs::WRLFile;
+
+/// Largest RGB distance between a stored and a computed minimap cell color which still counts as a match.
+/// Map editors average tile colors in different ways, so stored minimaps rarely match the computed one exactly.
+pub const MINIMAP_COLOR_TOLERANCE: f64 = 16.0;
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct MinimapReport {
+	pub cell_count: usize,
+	/// Cells whose stored palette index differs from the computed one.
+	pub mismatched_cells: usize,
+	/// Mismatched cells whose colors are further apart than `MINIMAP_COLOR_TOLERANCE`.
+	pub cells_beyond_tolerance: usize,
+	/// Mean RGB distance between stored and computed cell colors, over all cells.
+	pub average_color_distance: f64,
+	/// Largest RGB distance between a stored and a computed cell color.
+	pub max_color_distance: f64,
+	/// Whether the stored minimap was replaced with the computed one.
+	pub fixed: bool,
+}
+
+impl MinimapReport {
+	pub fn is_valid(&self) -> bool {
+		self.mismatched_cells == 0
+	}
+
+	/// Whether every stored cell color is close to the computed one, see `MINIMAP_COLOR_TOLERANCE`.
+	pub fn is_within_tolerance(&self) -> bool {
+		self.cells_beyond_tolerance == 0
+	}
+}
+
+/// Compares the stored minimap with the one computed from the bigmap.
+pub fn compare_minimap(wrl_file: &WRLFile) -> Result<MinimapReport, WrlError> {
+	compare_with_computed_minimap(wrl_file).map(|(report, _)| report)
+}
+
+/// Replaces the stored minimap with the one computed from the bigmap when they differ.
+/// The report describes the minimap as it was before the fix.
+pub fn fix_minimap(wrl_file: &mut WRLFile) -> Result<MinimapReport, WrlError> {
+	let (mut report, computed) = compare_with_computed_minimap(wrl_file)?;
+	if !report.is_valid() {
+		wrl_file.minimap = computed;
+		report.fixed = true;
+	}
+
+	Ok(report)
+}
+
+/// Returns the comparison report together with the computed minimap.
+fn compare_with_computed_minimap(wrl_file: &WRLFile) -> Result<(MinimapReport, Vec<u8>), WrlError> {
+	wrl_file.check_sections_size()?;
+	wrl_file.check_tile_indices()?;
+
+	let computed = generate_minimap(&wrl_file.bigmap, &wrl_file.tiles, &wrl_file.palette);
+
+	let mut mismatched_cells = 0;
+	let mut cells_beyond_tolerance = 0;
+	let mut total_color_distance = 0.0;
+	let mut max_color_distance: f64 = 0.0;
+
+	for (&stored_index, &computed_index) in wrl_file.minimap.iter().zip(&computed) {
+		if stored_index == computed_index {
+			continue;
+		}
+
+		mismatched_cells += 1;
+		let distance = palette_color_distance(&wrl_file.palette, stored_index, computed_index);
+		total_color_distance += distance;
+		max_color_distance = max_color_distance.max(distance);
+		if distance > MINIMAP_COLOR_TOLERANCE {
+			cells_beyond_tolerance += 1;
+		}
+	}
+
+	let cell_count = computed.len();
+	let report = MinimapReport {
+		cell_count,
+		mismatched_cells,
+		cells_beyond_tolerance,
+		average_color_distance: if cell_count > 0 { total_color_distance / cell_count as f64 } else { 0.0 },
+		max_color_distance,
+		fixed: false,
+	};
+
+	Ok((report, computed))
+}
+
+fn palette_color_distance(palette: &[u8], a: u8, b: u8) -> f64 {
+	let a = a as usize * 3;
+	let b = b as usize * 3;
+
+	(0..3)
+		.map(|channel| {
+			let delta = palette[a + channel] as f64 - palette[b + channel] as f64;
+			delta * delta
+		})
+		.sum::<f64>()
+		.sqrt()
+}
+
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use std::path::PathBuf;
+
+	use crate::run_test;
+	use crate::read_wrl_file;
+
+	#[test]
+	fn test_compare_minimap() {
+		run_test!({
+			// Arrange
+			let path = PathBuf::from("test_files/reference/REF.WRL");
+			let wrl_file = read_wrl_file(&path).unwrap();
+			let stored_minimap = wrl_file.minimap.clone();
+
+			// Act
+			let report = compare_minimap(&wrl_file).unwrap();
+
+			// Assert
+			// Cell k stores index k % 64, but its tile computes to (2 * k) % 64.
+			assert_eq!(report.cell_count, 256);
+			assert_eq!(report.mismatched_cells, 252);
+			// Cells whose stored and computed gray levels are at least 10 apart.
+			assert_eq!(report.cells_beyond_tolerance, 180);
+			assert!(!report.is_valid());
+			assert!(!report.is_within_tolerance());
+			assert!(!report.fixed);
+			assert_eq!(report.max_color_distance, (3.0 * 32.0 * 32.0f64).sqrt());
+			assert_eq!(wrl_file.minimap, stored_minimap);
+
+			let logs = get_captured_logs();
+			assert!(logs.is_empty());
+		});
+	}
+
+	#[test]
+	fn test_fix_minimap() {
+		run_test!({
+			// Arrange
+			let path = PathBuf::from("test_files/reference/REF.WRL");
+			let mut wrl_file = read_wrl_file(&path).unwrap();
+
+			// Act
+			let report = fix_minimap(&mut wrl_file).unwrap();
+			let revalidated = fix_minimap(&mut wrl_file).unwrap();
+
+			// Assert
+			assert!(report.fixed);
+			assert_eq!(report.mismatched_cells, 252);
+			assert_eq!(wrl_file.minimap[1], 2);
+			assert!(revalidated.is_valid());
+			assert!(revalidated.is_within_tolerance());
+			assert!(!revalidated.fixed);
+			assert_eq!(revalidated.average_color_distance, 0.0);
+
+			let logs = get_captured_logs();
+			assert!(logs.is_empty());
+		});
+	}
+}
@@ -0,0 +1,211 @@
+use serde::{Deserialize, Serialize};
+
+use crate::error::WrlError;
+use crate::structs::WRLFile;
+
+/// Terrain class stored per tile in the WRL pass table.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Passability {
+	Land = 0,
+	Water = 1,
+	Coast = 2,
+	Blocked = 3,
+}
+
+impl Passability {
+	pub const ALL: [Passability; 4] = [
+		Passability::Land,
+		Passability::Water,
+		Passability::Coast,
+		Passability::Blocked,
+	];
+
+	/// Decodes a pass table value, returning `None` for unknown values.
+	pub fn from_u8(value: u8) -> Option<Passability> {
+		match value {
+			0 => Some(Passability::Land),
+			1 => Some(Passability::Water),
+			2 => Some(Passability::Coast),
+			3 => Some(Passability::Blocked),
+			_ => None,
+		}
+	}
+
+	pub fn as_u8(&self) -> u8 {
+		*self as u8
+	}
+}
+
+impl WRLFile {
+	/// Returns the passability of the given tile.
+	pub fn tile_passability(&self, tile_index: u16) -> Result<Passability, WrlError> {
+		let value = match self.pass_table.get(tile_index as usize) {
+			Some(&value) if tile_index < self.tile_count => value,
+			_ => {
+				return Err(WrlError::TileNotFound { tile_index, tile_count: self.tile_count });
+			}
+		};
+
+		Passability::from_u8(value).ok_or(WrlError::InvalidPassability { tile_index, value })
+	}
+
+	/// Returns the passability of the map cell, resolved through the bigmap tile.
+	pub fn cell_passability(&self, x: u16, y: u16) -> Result<Passability, WrlError> {
+		if x >= self.width || y >= self.height {
+			return Err(WrlError::CellOutOfBounds { x, y });
+		}
+
+		let cell = y as usize * self.width as usize + x as usize;
+		let tile_index = match self.bigmap.get(cell) {
+			Some(&tile_index) => tile_index,
+			None => return Err(WrlError::CellOutOfBounds { x, y }),
+		};
+
+		self.tile_passability(tile_index)
+	}
+
+	/// Returns the passability of every map cell, in bigmap order.
+	/// Only tiles used by the bigmap are resolved, so unknown pass table values of unused tiles
+	/// are ignored. The error describes the first cell with an invalid tile or pass table value.
+	pub fn passability_map(&self) -> Result<Vec<Passability>, WrlError> {
+		self.bigmap
+			.iter()
+			.enumerate()
+			.map(|(cell, &tile_index)| {
+				if tile_index >= self.tile_count {
+					return Err(WrlError::InvalidTileIndex { cell, tile_index, tile_count: self.tile_count });
+				}
+
+				self.tile_passability(tile_index)
+			})
+			.collect()
+	}
+
+	/// Sets the passability of the given tile, affecting every cell which uses it.
+	pub fn set_tile_passability(&mut self, tile_index: u16, passability: Passability) -> Result<(), WrlError> {
+		match self.pass_table.get_mut(tile_index as usize) {
+			Some(value) if tile_index < self.tile_count => {
+				*value = passability.as_u8();
+				Ok(())
+			}
+			_ => Err(WrlError::TileNotFound { tile_index, tile_count: self.tile_count }),
+		}
+	}
+}
+
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use std::path::PathBuf;
+
+	use crate::run_test;
+	use crate::read_wrl_file;
+
+	#[test]
+	fn test_cell_passability() {
+		run_test!({
+			// Arrange
+			let mut wrl_file = read_wrl_file(&PathBuf::from("test_files/reference/REF.WRL")).unwrap();
+			// Cell (1, 0) uses tile 2, cell (2, 0) uses tile 4.
+			wrl_file.pass_table[2] = 1;
+			wrl_file.pass_table[4] = 3;
+
+			// Act & Assert
+			assert_eq!(wrl_file.cell_passability(0, 0), Ok(Passability::Land));
+			assert_eq!(wrl_file.cell_passability(1, 0), Ok(Passability::Water));
+			assert_eq!(wrl_file.cell_passability(2, 0), Ok(Passability::Blocked));
+			assert_eq!(wrl_file.cell_passability(16, 0), Err(WrlError::CellOutOfBounds { x: 16, y: 0 }));
+
+			let logs = get_captured_logs();
+			assert!(logs.is_empty());
+		});
+	}
+
+	#[test]
+	fn test_passability_map() {
+		run_test!({
+			// Arrange
+			let mut wrl_file = read_wrl_file(&PathBuf::from("test_files/reference/REF.WRL")).unwrap();
+			wrl_file.pass_table[2] = 1;
+
+			// Act
+			let passability_map = wrl_file.passability_map().unwrap();
+
+			// Assert
+			assert_eq!(passability_map.len(), 256);
+			assert_eq!(passability_map[0], Passability::Land);
+			assert_eq!(passability_map[1], Passability::Water);
+			assert_eq!(passability_map[33], Passability::Water);
+			assert_eq!(passability_map.iter().filter(|&&p| p == Passability::Water).count(), 8);
+
+			let logs = get_captured_logs();
+			assert!(logs.is_empty());
+		});
+	}
+
+	#[test]
+	fn test_passability_map_unknown_values() {
+		run_test!({
+			// Arrange
+			let mut wrl_file = read_wrl_file(&PathBuf::from("test_files/reference/REF.WRL")).unwrap();
+			// The bigmap only uses even tiles.
+			wrl_file.pass_table[3] = 7;
+			let mut invalid_wrl_file = wrl_file.clone();
+			invalid_wrl_file.pass_table[4] = 9;
+			invalid_wrl_file.pass_table[2] = 8;
+
+			// Act
+			let passability_map = wrl_file.passability_map();
+			let invalid_result = invalid_wrl_file.passability_map();
+
+			// Assert
+			assert_eq!(passability_map.map(|passability_map| passability_map.len()), Ok(256));
+			// Cell 1 uses tile 2, which comes before cell 2 with tile 4.
+			assert_eq!(invalid_result, Err(WrlError::InvalidPassability { tile_index: 2, value: 8 }));
+
+			let logs = get_captured_logs();
+			assert!(logs.is_empty());
+		});
+	}
+
+	#[test]
+	fn test_cell_passability_unknown_value() {
+		run_test!({
+			// Arrange
+			let mut wrl_file = read_wrl_file(&PathBuf::from("test_files/reference/REF.WRL")).unwrap();
+			wrl_file.pass_table[2] = 7;
+
+			// Act
+			let result = wrl_file.cell_passability(1, 0);
+
+			// Assert
+			assert_eq!(result, Err(WrlError::InvalidPassability { tile_index: 2, value: 7 }));
+
+			let logs = get_captured_logs();
+			assert!(logs.is_empty());
+		});
+	}
+
+	#[test]
+	fn test_set_tile_passability() {
+		run_test!({
+			// Arrange
+			let mut wrl_file = read_wrl_file(&PathBuf::from("test_files/reference/REF.WRL")).unwrap();
+
+			// Act
+			let result = wrl_file.set_tile_passability(4, Passability::Coast);
+			let missing_result = wrl_file.set_tile_passability(64, Passability::Coast);
+
+			// Assert
+			assert!(result.is_ok());
+			assert_eq!(wrl_file.pass_table[4], 2);
+			assert_eq!(wrl_file.cell_passability(2, 0), Ok(Passability::Coast));
+			assert_eq!(missing_result, Err(WrlError::TileNotFound { tile_index: 64, tile_count: 64 }));
+
+			let logs = get_captured_logs();
+			assert!(logs.is_empty());
+		});
+	}
+}
@@ -0,0 +1,116 @@
+/// Inclusive range of palette indices which the game rotates to animate the terrain.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PaletteCycleRange {
+	pub start: u8,
+	pub end: u8,
+}
+
+/// Palette ranges cycled by the game to animate water and shorelines.
+/// They are the same for all planets, only the colors differ.
+// The original game engine rotates these hard-coded index ranges every animation tick, the map
+// files do not describe them. Every planet palette keeps its water and shoreline gradients at
+// indices 96 to 127, split into three 7-color, one 6-color and one 5-color ranges.
+// The ranges come back to their initial colors after `palette_cycle_length` = lcm(7, 6, 5) = 210 steps.
+pub const PLANET_PALETTE_CYCLE_RANGES: [PaletteCycleRange; 5] = [
+	PaletteCycleRange { start: 96, end: 102 },
+	PaletteCycleRange { start: 103, end: 109 },
+	PaletteCycleRange { start: 110, end: 116 },
+	PaletteCycleRange { start: 117, end: 122 },
+	PaletteCycleRange { start: 123, end: 127 },
+];
+
+/// Returns a copy of the palette with every cycle range rotated by `step` entries.
+/// Each color moves towards the end of its range and wraps around to its start.
+pub fn cycle_palette(palette: &[u8], ranges: &[PaletteCycleRange], step: usize) -> Vec<u8> {
+	let mut cycled = palette.to_vec();
+
+	for range in ranges {
+		let start = range.start as usize * 3;
+		let end = (range.end as usize + 1) * 3;
+		if range.start > range.end || end > palette.len() {
+			continue;
+		}
+
+		let range_length = range.end as usize - range.start as usize + 1;
+		cycled[start..end].rotate_right((step % range_length) * 3);
+	}
+
+	cycled
+}
+
+/// Number of steps after which all ranges are back to their initial colors,
+/// the least common multiple of the range lengths. Invalid ranges are ignored, as in `cycle_palette`.
+pub fn palette_cycle_length(ranges: &[PaletteCycleRange]) -> usize {
+	ranges
+		.iter()
+		.filter(|range| range.start <= range.end)
+		.map(|range| range.end as usize - range.start as usize + 1)
+		.fold(1, |length, range_length| length / gcd(length, range_length) * range_length)
+}
+
+fn gcd(a: usize, b: usize) -> usize {
+	if b == 0 { a } else { gcd(b, a % b) }
+}
+
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	use crate::run_test;
+
+	#[test]
+	fn test_cycle_palette() {
+		run_test!({
+			// Arrange
+			let palette: Vec<u8> = (0..8).flat_map(|i| [i, i, i]).collect();
+			let ranges = [
+				PaletteCycleRange { start: 1, end: 3 },
+				PaletteCycleRange { start: 5, end: 6 },
+				PaletteCycleRange { start: 7, end: 9 },
+			];
+
+			// Act
+			let first_frame = cycle_palette(&palette, &ranges, 0);
+			let second_frame = cycle_palette(&palette, &ranges, 1);
+			let fourth_frame = cycle_palette(&palette, &ranges, 3);
+
+			// Assert
+			assert_eq!(first_frame, palette);
+			let second_indices: Vec<u8> = second_frame.chunks_exact(3).map(|color| color[0]).collect();
+			assert_eq!(second_indices, vec![0, 3, 1, 2, 4, 6, 5, 7]);
+			let fourth_indices: Vec<u8> = fourth_frame.chunks_exact(3).map(|color| color[0]).collect();
+			assert_eq!(fourth_indices, vec![0, 1, 2, 3, 4, 6, 5, 7]);
+
+			let logs = get_captured_logs();
+			assert!(logs.is_empty());
+		});
+	}
+
+	#[test]
+	fn test_palette_cycle_length() {
+		run_test!({
+			// Arrange
+			let ranges = [
+				PaletteCycleRange { start: 1, end: 3 },
+				PaletteCycleRange { start: 5, end: 6 },
+				PaletteCycleRange { start: 9, end: 7 },
+			];
+
+			// Act
+			let cycle_length = palette_cycle_length(&ranges);
+			let planet_cycle_length = palette_cycle_length(&PLANET_PALETTE_CYCLE_RANGES);
+
+			// Assert
+			assert_eq!(cycle_length, 6);
+			assert_eq!(planet_cycle_length, 210);
+			assert_eq!(palette_cycle_length(&[]), 1);
+			let palette: Vec<u8> = (0..=255).flat_map(|i| [i, i, i]).collect();
+			assert_eq!(cycle_palette(&palette, &PLANET_PALETTE_CYCLE_RANGES, planet_cycle_length), palette);
+			assert_ne!(cycle_palette(&palette, &PLANET_PALETTE_CYCLE_RANGES, 7), palette);
+
+			let logs = get_captured_logs();
+			assert!(logs.is_empty());
+		});
+	}
+}
@@ -0,0 +1,259 @@
+use std::io::Write;
+
+use rayon::prelude::*;
+
+use crate::consts::{TILE_DATA_SIZE, TILE_SIZE};
+use crate::cycle_palette::{cycle_palette, palette_cycle_length, PaletteCycleRange};
+use crate::error::WrlError;
+use crate::generate_bigmap_preview::{
+	BigmapSampler, PixelRect, PreviewEffects, PreviewFit, PreviewLayout, PreviewOptions,
+};
+use crate::file::wrl_view::WrlView;
+use crate::tile_mipmaps::TileMipmaps;
+
+/// Delay between animation frames, in milliseconds.
+pub const PALETTE_CYCLE_FRAME_DELAY_MS: u16 = 100;
+
+/// Preview pixel of the first frame, with the share of its map region drawn in each cycled color.
+struct AnimatedPixel {
+	region: PixelRect,
+	color: [u8; 3],
+	cycled_colors: Vec<(usize, f32)>,
+}
+
+/// Generate RGBA pixel buffers of the bigmap preview for one full palette cycle, see `palette_cycle_length`.
+/// Frames are letterboxed whatever the fit option, so every frame has exactly the target size.
+/// The map is sampled once, later frames only recolor the pixels drawn with cycled colors.
+/// Fails only if the pass table of the file is truncated.
+pub fn generate_bigmap_animation_frames(
+	wrl_view: &WrlView,
+	options: PreviewOptions,
+	ranges: &[PaletteCycleRange],
+) -> Result<Vec<Vec<u8>>, WrlError> {
+	let options = PreviewOptions { fit: PreviewFit::Letterbox, ..options };
+	let frame_count = palette_cycle_length(ranges);
+	let layout = PreviewLayout::new(wrl_view.width(), wrl_view.height(), options);
+	let frame_size = layout.output_width * layout.output_height * 4;
+	if layout.is_empty() {
+		return Ok(vec![vec![0u8; frame_size]; frame_count]);
+	}
+
+	let bigmap = wrl_view.bigmap();
+	let tiles = wrl_view.tiles();
+	let palette = wrl_view.palette();
+
+	let mipmaps = TileMipmaps::for_bigmap(&bigmap, tiles, palette);
+	let sampler = BigmapSampler {
+		mipmaps: &mipmaps,
+		bigmap: &bigmap,
+		width: wrl_view.width() as usize,
+		height: wrl_view.height() as usize,
+	};
+	let effects = PreviewEffects {
+		bigmap: &bigmap,
+		pass_table: wrl_view.pass_table()?,
+		width: wrl_view.width(),
+		height: wrl_view.height(),
+		options,
+	};
+
+	let mut cycled = [false; 256];
+	for range in ranges.iter().filter(|range| range.start <= range.end) {
+		cycled[range.start as usize..=range.end as usize].fill(true);
+	}
+	let cycled_tiles: Vec<bool> = tiles
+		.par_chunks_exact(TILE_DATA_SIZE)
+		.map(|tile| tile.iter().any(|&color_index| cycled[color_index as usize]))
+		.collect();
+
+	let pixels: Vec<Option<AnimatedPixel>> = (0..layout.output_width * layout.output_height)
+		.into_par_iter()
+		.map(|pixel_index| {
+			let region = layout.source_region(pixel_index % layout.output_width, pixel_index / layout.output_width)?;
+			Some(AnimatedPixel {
+				region,
+				color: sampler.average_color(&region),
+				cycled_colors: cycled_color_shares(wrl_view, &bigmap, &region, &cycled, &cycled_tiles),
+			})
+		})
+		.collect();
+
+	let frames = (0..frame_count)
+		.into_par_iter()
+		.map(|step| {
+			let cycled_palette = cycle_palette(palette, ranges, step);
+			let mut frame = vec![0u8; frame_size];
+
+			for (pixel_index, (pixel, animated_pixel)) in frame.chunks_exact_mut(4).zip(&pixels).enumerate() {
+				pixel[3] = 255;
+				let Some(animated_pixel) = animated_pixel else {
+					continue;
+				};
+
+				let color = std::array::from_fn(|channel| {
+					let color_shift: f32 = animated_pixel
+						.cycled_colors
+						.iter()
+						.map(|&(color_index, share)| {
+							let palette_index = color_index * 3 + channel;
+							(cycled_palette[palette_index] as f32 - palette[palette_index] as f32) * share
+						})
+						.sum();
+					(animated_pixel.color[channel] as f32 + color_shift).round().clamp(0.0, 255.0) as u8
+				});
+				let y = pixel_index / layout.output_width;
+				pixel[..3].copy_from_slice(&effects.apply(color, &animated_pixel.region, y));
+			}
+
+			frame
+		})
+		.collect();
+
+	Ok(frames)
+}
+
+/// Returns the cycled palette indices used in the map region, with the share of region pixels using them.
+/// Only cells whose tile uses a cycled color are read.
+fn cycled_color_shares(
+	wrl_view: &WrlView,
+	bigmap: &[u16],
+	region: &PixelRect,
+	cycled: &[bool; 256],
+	cycled_tiles: &[bool],
+) -> Vec<(usize, f32)> {
+	let mut counts = [0u32; 256];
+	let width = wrl_view.width() as usize;
+	let region_end_x = (region.x + region.width).min(width * TILE_SIZE);
+	let region_end_y = (region.y + region.height).min(wrl_view.height() as usize * TILE_SIZE);
+
+	for y in region.y..region_end_y {
+		let mut x = region.x;
+		while x < region_end_x {
+			let cell_x = x / TILE_SIZE;
+			let cell_end_x = ((cell_x + 1) * TILE_SIZE).min(region_end_x);
+			let tile_index = bigmap.get((y / TILE_SIZE) * width + cell_x).map(|&tile_index| tile_index as usize);
+
+			if let Some(tile_index) = tile_index
+				&& cycled_tiles.get(tile_index) == Some(&true)
+			{
+				let row_start = tile_index * TILE_DATA_SIZE + (y % TILE_SIZE) * TILE_SIZE;
+				let row = &wrl_view.tiles()[row_start + x % TILE_SIZE..row_start + (cell_end_x - 1) % TILE_SIZE + 1];
+				for &color_index in row {
+					counts[color_index as usize] += cycled[color_index as usize] as u32;
+				}
+			}
+
+			x = cell_end_x;
+		}
+	}
+
+	let pixel_count = (region.width * region.height) as f32;
+	counts
+		.iter()
+		.enumerate()
+		.filter(|&(_, &count)| count > 0)
+		.map(|(color_index, &count)| (color_index, count as f32 / pixel_count))
+		.collect()
+}
+
+/// Encodes RGBA frames of equal size as an endlessly looping animated PNG.
+pub fn write_animated_png<W: Write>(
+	frames: &[Vec<u8>],
+	width: u32,
+	height: u32,
+	frame_delay_ms: u16,
+	writer: W,
+) -> Result<(), WrlError> {
+	let mut encoder = png::Encoder::new(writer, width, height);
+	encoder.set_color(png::ColorType::Rgba);
+	encoder.set_depth(png::BitDepth::Eight);
+	encoder
+		.set_animated(frames.len() as u32, 0)
+		.and_then(|_| encoder.set_frame_delay(frame_delay_ms, 1000))
+		.map_err(|e| WrlError::Image(e.to_string()))?;
+
+	let mut png_writer = encoder.write_header().map_err(|e| WrlError::Image(e.to_string()))?;
+	for frame in frames {
+		png_writer.write_image_data(frame).map_err(|e| WrlError::Image(e.to_string()))?;
+	}
+
+	png_writer.finish().map_err(|e| WrlError::Image(e.to_string()))
+}
+
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use std::path::PathBuf;
+
+	use crate::run_test;
+	use crate::generate_bigmap_preview::generate_bigmap_preview;
+	use crate::PLANET_PALETTE_CYCLE_RANGES;
+
+	#[test]
+	fn test_generate_bigmap_animation_frames() {
+		run_test!({
+			// Arrange
+			let wrl_view = WrlView::open(&PathBuf::from("test_files/reference/REF.WRL")).unwrap();
+			let ranges = [PaletteCycleRange { start: 0, end: 63 }];
+			let options = PreviewOptions { target_pixel_width: 16, target_pixel_height: 16, ..PreviewOptions::default() };
+
+			// Act
+			let frames = generate_bigmap_animation_frames(&wrl_view, options, &ranges).unwrap();
+
+			// Assert
+			assert_eq!(frames.len(), 64);
+			assert_ne!(frames[0], frames[1]);
+			assert_ne!(frames[1], frames[2]);
+
+			// Recolored frames match a full render of the cycled palette, up to rounding.
+			for step in [0, 1, 17, 63] {
+				let palette = cycle_palette(wrl_view.palette(), &ranges, step);
+				let rendered = generate_bigmap_preview(
+					&wrl_view.bigmap(),
+					&TileMipmaps::for_bigmap(&wrl_view.bigmap(), wrl_view.tiles(), &palette),
+					wrl_view.pass_table().unwrap(),
+					wrl_view.width(),
+					wrl_view.height(),
+					options,
+				);
+				assert_eq!(frames[step].len(), rendered.len());
+				for (recolored, rendered) in frames[step].iter().zip(&rendered) {
+					assert!(recolored.abs_diff(*rendered) <= 2, "step {}: {} != {}", step, recolored, rendered);
+				}
+			}
+
+			// The reference map only uses palette entries 0 to 63, which the planet ranges do not cycle.
+			let planet_frames = generate_bigmap_animation_frames(&wrl_view, options, &PLANET_PALETTE_CYCLE_RANGES).unwrap();
+			assert_eq!(planet_frames.len(), 210);
+			assert!(planet_frames.iter().all(|frame| *frame == planet_frames[0]));
+
+			let logs = get_captured_logs();
+			assert!(logs.is_empty());
+		});
+	}
+
+	#[test]
+	fn test_write_animated_png() {
+		run_test!({
+			// Arrange
+			let frames = vec![vec![255u8; 4 * 4 * 4], vec![0u8; 4 * 4 * 4]];
+			let mut png_data = Vec::new();
+
+			// Act
+			let result = write_animated_png(&frames, 4, 4, PALETTE_CYCLE_FRAME_DELAY_MS, &mut png_data);
+
+			// Assert
+			assert!(result.is_ok());
+
+			let decoder = png::Decoder::new(std::io::Cursor::new(&png_data));
+			let reader = decoder.read_info().unwrap();
+			let animation_control = reader.info().animation_control.unwrap();
+			assert_eq!(animation_control.num_frames, 2);
+			assert_eq!(animation_control.num_plays, 0);
+
+			let logs = get_captured_logs();
+			assert!(logs.is_empty());
+		});
+	}
+}
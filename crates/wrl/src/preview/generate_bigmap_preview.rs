@@ -1,161 +1,373 @@
-use crate::{TILE_DATA_SIZE, TILE_SIZE};
+use rayon::prelude::*;
 
+use crate::{Passability, TILE_SIZE};
+use crate::preview::tile_mipmaps::{TileMipmaps, mip_level_size};
+
+
+/// Additional information drawn on top of the bigmap preview.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum PreviewOverlay {
+	#[default]
+	None,
+	/// Tints every cell by its pass table class.
+	Passability,
+}
+
+impl PreviewOverlay {
+	pub fn from_name(name: &str) -> Option<PreviewOverlay> {
+		match name {
+			"none" => Some(PreviewOverlay::None),
+			"passability" => Some(PreviewOverlay::Passability),
+			_ => None,
+		}
+	}
+
+	pub fn name(&self) -> &'static str {
+		match self {
+			PreviewOverlay::None => "none",
+			PreviewOverlay::Passability => "passability",
+		}
+	}
+}
+
+/// Placement of the map in a target size with a different aspect ratio.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum PreviewFit {
+	/// Scales the whole map into the target size, the preview shrinks to the map aspect ratio.
+	Fit,
+	/// Scales the map to cover the whole target size, cropping the map edges.
+	Fill,
+	/// Scales the whole map into the target size and pads the rest with black bars.
+	#[default]
+	Letterbox,
+}
+
+impl PreviewFit {
+	pub fn from_name(name: &str) -> Option<PreviewFit> {
+		match name {
+			"fit" => Some(PreviewFit::Fit),
+			"fill" => Some(PreviewFit::Fill),
+			"letterbox" => Some(PreviewFit::Letterbox),
+			_ => None,
+		}
+	}
+
+	pub fn name(&self) -> &'static str {
+		match self {
+			PreviewFit::Fit => "fit",
+			PreviewFit::Fill => "fill",
+			PreviewFit::Letterbox => "letterbox",
+		}
+	}
+
+	/// Returns the pixel size of the preview generated for the map and target size.
+	/// The width and height are in map cells, not pixels.
+	pub fn output_size(
+		&self,
+		width: u16,
+		height: u16,
+		target_pixel_width: usize,
+		target_pixel_height: usize,
+	) -> (usize, usize) {
+		match self {
+			PreviewFit::Fit => fit_size(width as usize, height as usize, target_pixel_width, target_pixel_height),
+			PreviewFit::Fill | PreviewFit::Letterbox => (target_pixel_width, target_pixel_height),
+		}
+	}
+}
+
+/// Size and look of a bigmap preview.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct PreviewOptions {
+	/// Target width in pixels, the preview width depends on the fit mode.
+	pub target_pixel_width: usize,
+	/// Target height in pixels, the preview height depends on the fit mode.
+	pub target_pixel_height: usize,
+	/// Darkens every second row.
+	pub scanline: bool,
+	pub overlay: PreviewOverlay,
+	pub fit: PreviewFit,
+}
+
+impl Default for PreviewOptions {
+	fn default() -> Self {
+		PreviewOptions {
+			target_pixel_width: 256,
+			target_pixel_height: 256,
+			scanline: false,
+			overlay: PreviewOverlay::None,
+			fit: PreviewFit::Letterbox,
+		}
+	}
+}
+
+impl PreviewOptions {
+	/// Returns the pixel size of the preview generated for the map, see `PreviewFit::output_size`.
+	/// The width and height are in map cells, not pixels.
+	pub fn output_size(&self, width: u16, height: u16) -> (usize, usize) {
+		self.fit.output_size(width, height, self.target_pixel_width, self.target_pixel_height)
+	}
+}
+
+/// Tint color of the passability overlay. Unknown pass table values are not tinted.
+pub fn passability_tint_color(passability: Passability) -> [u8; 3] {
+	match passability {
+		Passability::Land => [40, 200, 40],
+		Passability::Water => [40, 90, 255],
+		Passability::Coast => [240, 220, 60],
+		Passability::Blocked => [230, 30, 30],
+	}
+}
 
 /// Generate an RGBA pixel buffer for the bigmap preview scaled down to given size.
 /// The width and height are in map cells, not pixels.
+/// The buffer size depends on the fit mode, see `PreviewOptions::output_size`.
+/// The mipmaps need to cover the tiles referenced by the bigmap, see `TileMipmaps::for_bigmap`.
+/// The pass table is only used by the passability overlay.
 pub fn generate_bigmap_preview(
 	bigmap: &[u16],
-	tiles: &[u8],
-	palette: &[u8],
+	mipmaps: &TileMipmaps,
+	pass_table: &[u8],
 	width: u16,
 	height: u16,
-	target_pixel_width: usize,
-	target_pixel_height: usize,
-	scanline: bool,
+	options: PreviewOptions,
 ) -> Vec<u8> {
-	let mut preview = vec![0u8; target_pixel_width * target_pixel_height * 4];
-
-	let mut step = 1;
-
-	if target_pixel_width < (TILE_SIZE * width as usize) / 16 {
-		step = 16;
-	} else if target_pixel_width < (TILE_SIZE * width as usize) / 8 {
-		step = 8;
-	} else if target_pixel_width < (TILE_SIZE * width as usize) / 4 {
-		step = 4;
-	} else if target_pixel_width < (TILE_SIZE * width as usize) / 2 {
-		step = 2;
-	}
-
-	let map_pixel_width = width as usize * TILE_SIZE;
-	let map_pixel_height = height as usize * TILE_SIZE;
-
-    for y in 0..target_pixel_height {
-        for x in 0..target_pixel_width {
-            let src_x_start = x * map_pixel_width / target_pixel_width;
-            let src_x_end = ((x + 1) * map_pixel_width / target_pixel_width).min(map_pixel_width - 1);
-            let src_y_start = y * map_pixel_height / target_pixel_height;
-            let src_y_end = ((y + 1) * map_pixel_width / target_pixel_height).min(map_pixel_height - 1);
-
-            let color = interpolate_pixel_color(
-                bigmap,
-                tiles,
-                palette,
-                src_x_start,
-                src_x_end,
-                src_y_start,
-                src_y_end,
-                width as usize,
-                height as usize,
-				step,
-            );
-
-            let index = (y * target_pixel_width + x) * 4;
-			if scanline && (y % 2 == 1) {
-				preview[index + 0] = (color[0] as f32 * 0.5) as u8;
-				preview[index + 1] = (color[1] as f32 * 0.5) as u8;
-				preview[index + 2] = (color[2] as f32 * 0.5) as u8;
-			} else {
-				preview[index + 0] = color[0];
-				preview[index + 1] = color[1];
-				preview[index + 2] = color[2];
-			}
-            preview[index + 3] = 255;
-        }
-    }
+	let layout = PreviewLayout::new(width, height, options);
+	let mut preview = vec![0u8; layout.output_width * layout.output_height * 4];
+	if layout.is_empty() {
+		return preview;
+	}
+
+	let sampler = BigmapSampler {
+		mipmaps,
+		bigmap,
+		width: width as usize,
+		height: height as usize,
+	};
+	let effects = PreviewEffects { bigmap, pass_table, width, height, options };
+
+	preview.par_chunks_mut(layout.output_width * 4).enumerate().for_each(|(y, row)| {
+		for (x, pixel) in row.chunks_exact_mut(4).enumerate() {
+			pixel[3] = 255;
+			let Some(region) = layout.source_region(x, y) else {
+				continue;
+			};
+
+			let color = effects.apply(sampler.average_color(&region), &region, y);
+			pixel[..3].copy_from_slice(&color);
+		}
+	});
 
 	preview
 }
 
-/// Interpolate pixel color for a given region in the bigmap.
-/// The width and height are in map cells, not pixels.
-fn interpolate_pixel_color(
-    bigmap: &[u16],
-    tiles: &[u8],
-    palette: &[u8],
-    start_x: usize,
-    end_x: usize,
-    start_y: usize,
-    end_y: usize,
-    width: usize,
-    height: usize,
-    step: usize,
-) -> Vec<u8> {
-    let mut color = [0u32; 3];
-    let mut pixels_interpolated = 0u32;
-
-    let palette_len_minus_3 = palette.len().saturating_sub(3);
-
-    // Calculate bounds for the bigmap tile loop
-    let bigmap_start_tile_x = start_x / TILE_SIZE;
-    let bigmap_end_tile_x = end_x / TILE_SIZE;
-    let bigmap_start_tile_y = start_y / TILE_SIZE;
-    let bigmap_end_tile_y = end_y / TILE_SIZE;
-
-    // Iterate over the *tiles* that cover the interpolation region
-    for bigmap_tile_y in bigmap_start_tile_y..=(bigmap_end_tile_y.min(height.saturating_sub(1))) {
-        // Pre-calculate common offsets for this tile row
-        let bigmap_y_offset = bigmap_tile_y * width;
-
-        // Calculate the actual pixel y-range within this specific tile
-        let current_tile_pixel_y_start = start_y.max(bigmap_tile_y * TILE_SIZE);
-        let current_tile_pixel_y_end = end_y.min((bigmap_tile_y + 1) * TILE_SIZE - 1);
-
-        for bigmap_tile_x in bigmap_start_tile_x..=(bigmap_end_tile_x.min(width.saturating_sub(1))) {
-            // Get tile_id once per tile
-            let bigmap_index = bigmap_y_offset + bigmap_tile_x;
-            if bigmap_index >= bigmap.len() {
-                continue; // Should ideally not happen if outer loop bounds are correct
-            }
-            let tile_id = bigmap[bigmap_index] as usize;
-
-            // Pre-calculate base offset for tile data
-            let tile_base_data_offset = tile_id * TILE_DATA_SIZE;
-
-            // Calculate the actual pixel x-range within this specific tile
-            let current_tile_pixel_x_start = start_x.max(bigmap_tile_x * TILE_SIZE);
-            let current_tile_pixel_x_end = end_x.min((bigmap_tile_x + 1) * TILE_SIZE - 1);
-
-            // Now, iterate only over the relevant pixels *within this tile*
-            for y_in_tile_pixel in (current_tile_pixel_y_start..=current_tile_pixel_y_end).step_by(step) {
-                let tile_y_in_tile = y_in_tile_pixel % TILE_SIZE;
-                let tile_row_offset = tile_base_data_offset + tile_y_in_tile * TILE_SIZE; // Pre-calculate row offset
-
-                for x_in_tile_pixel in (current_tile_pixel_x_start..=current_tile_pixel_x_end).step_by(step) {
-                    let tile_x_in_tile = x_in_tile_pixel % TILE_SIZE;
-
-                    let tile_data_offset = tile_row_offset + tile_x_in_tile;
-
-                    // Bounds check for tiles array
-                    if tile_data_offset >= tiles.len() {
-                        continue;
-                    }
-
-                    let tile_value = tiles[tile_data_offset];
-
-                    let palette_start_index = (tile_value as usize) * 3;
-                    if palette_start_index > palette_len_minus_3 {
-                        continue;
-                    }
-
-                    color[0] += palette[palette_start_index] as u32;
-                    color[1] += palette[palette_start_index + 1] as u32;
-                    color[2] += palette[palette_start_index + 2] as u32;
-                    pixels_interpolated += 1;
-                }
-            }
-        }
-    }
-
-    let mut final_color = Vec::with_capacity(3);
-    if pixels_interpolated > 0 {
-        final_color.push((color[0] / pixels_interpolated) as u8);
-        final_color.push((color[1] / pixels_interpolated) as u8);
-        final_color.push((color[2] / pixels_interpolated) as u8);
-    } else {
-        final_color.extend_from_slice(&[0, 0, 0]);
-    }
-    final_color
+/// Map pixels drawn to every preview pixel, following the fit mode.
+pub(crate) struct PreviewLayout {
+	pub(crate) output_width: usize,
+	pub(crate) output_height: usize,
+	/// Map pixels which are shown.
+	source: PixelRect,
+	/// Preview pixels the shown map pixels are drawn to.
+	destination: PixelRect,
+}
+
+impl PreviewLayout {
+	pub(crate) fn new(width: u16, height: u16, options: PreviewOptions) -> PreviewLayout {
+		let (output_width, output_height) = options.output_size(width, height);
+		let map_pixel_width = width as usize * TILE_SIZE;
+		let map_pixel_height = height as usize * TILE_SIZE;
+		let whole_map = PixelRect { x: 0, y: 0, width: map_pixel_width, height: map_pixel_height };
+		let whole_preview = PixelRect { x: 0, y: 0, width: output_width, height: output_height };
+
+		let (source, destination) = match options.fit {
+			PreviewFit::Fit => (whole_map, whole_preview),
+			PreviewFit::Fill => {
+				let (crop_width, crop_height) = fit_size(output_width, output_height, map_pixel_width, map_pixel_height);
+				(PixelRect::centered(crop_width, crop_height, map_pixel_width, map_pixel_height), whole_preview)
+			}
+			PreviewFit::Letterbox => {
+				let (fit_width, fit_height) = fit_size(width as usize, height as usize, output_width, output_height);
+				(whole_map, PixelRect::centered(fit_width, fit_height, output_width, output_height))
+			}
+		};
+
+		PreviewLayout { output_width, output_height, source, destination }
+	}
+
+	/// True when there is nothing to draw, either the preview or the map has no size.
+	pub(crate) fn is_empty(&self) -> bool {
+		self.output_width == 0 || self.output_height == 0 || self.source.width == 0 || self.source.height == 0
+	}
+
+	/// Returns the map pixels drawn to the preview pixel, `None` for pixels outside of the drawn area.
+	pub(crate) fn source_region(&self, x: usize, y: usize) -> Option<PixelRect> {
+		if !self.destination.contains(x, y) {
+			return None;
+		}
+
+		let source = &self.source;
+		let destination = &self.destination;
+		let destination_x = x - destination.x;
+		let destination_y = y - destination.y;
+		let src_x_start = source.x + destination_x * source.width / destination.width;
+		let src_x_end = (source.x + (destination_x + 1) * source.width / destination.width).max(src_x_start + 1);
+		let src_y_start = source.y + destination_y * source.height / destination.height;
+		let src_y_end = (source.y + (destination_y + 1) * source.height / destination.height).max(src_y_start + 1);
+
+		Some(PixelRect {
+			x: src_x_start,
+			y: src_y_start,
+			width: src_x_end - src_x_start,
+			height: src_y_end - src_y_start,
+		})
+	}
+}
+
+/// Overlay and scanline effects, applied to the averaged map colors.
+pub(crate) struct PreviewEffects<'a> {
+	pub(crate) bigmap: &'a [u16],
+	pub(crate) pass_table: &'a [u8],
+	/// Map width in cells.
+	pub(crate) width: u16,
+	/// Map height in cells.
+	pub(crate) height: u16,
+	pub(crate) options: PreviewOptions,
+}
+
+impl PreviewEffects<'_> {
+	/// Returns the color of the preview pixel in row `y`, drawn from the map region with the given average color.
+	pub(crate) fn apply(&self, mut color: [u8; 3], region: &PixelRect, y: usize) -> [u8; 3] {
+		if self.options.overlay == PreviewOverlay::Passability {
+			let cell_x = ((2 * region.x + region.width) / 2 / TILE_SIZE).min(self.width as usize - 1);
+			let cell_y = ((2 * region.y + region.height) / 2 / TILE_SIZE).min(self.height as usize - 1);
+			let passability = self
+				.bigmap
+				.get(cell_y * self.width as usize + cell_x)
+				.and_then(|&tile_index| self.pass_table.get(tile_index as usize))
+				.and_then(|&value| Passability::from_u8(value));
+			if let Some(passability) = passability {
+				let tint = passability_tint_color(passability);
+				for channel in 0..3 {
+					color[channel] = ((color[channel] as u16 + tint[channel] as u16) / 2) as u8;
+				}
+			}
+		}
+
+		if self.options.scanline && (y % 2 == 1) {
+			color = color.map(|channel| (channel as f32 * 0.5) as u8);
+		}
+
+		color
+	}
+}
+
+/// Largest size with the aspect ratio of the content which fits into the box.
+fn fit_size(content_width: usize, content_height: usize, box_width: usize, box_height: usize) -> (usize, usize) {
+	if content_width == 0 || content_height == 0 {
+		return (box_width, box_height);
+	}
+
+	let (width, height) = if box_width * content_height <= box_height * content_width {
+		(box_width, (box_width * content_height + content_width / 2) / content_width)
+	} else {
+		((box_height * content_width + content_height / 2) / content_height, box_height)
+	};
+
+	(width.max(1).min(box_width), height.max(1).min(box_height))
+}
+
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct PixelRect {
+	pub(crate) x: usize,
+	pub(crate) y: usize,
+	pub(crate) width: usize,
+	pub(crate) height: usize,
+}
+
+impl PixelRect {
+	/// Rectangle of the given size centered in the outer size.
+	fn centered(width: usize, height: usize, outer_width: usize, outer_height: usize) -> PixelRect {
+		PixelRect {
+			x: (outer_width - width) / 2,
+			y: (outer_height - height) / 2,
+			width,
+			height,
+		}
+	}
+
+	fn contains(&self, x: usize, y: usize) -> bool {
+		x >= self.x && x < self.x + self.width && y >= self.y && y < self.y + self.height
+	}
+}
+
+/// Averages bigmap colors over pixel regions using the tile mip levels.
+pub(crate) struct BigmapSampler<'a> {
+	pub(crate) mipmaps: &'a TileMipmaps,
+	pub(crate) bigmap: &'a [u16],
+	/// Map width in cells.
+	pub(crate) width: usize,
+	/// Map height in cells.
+	pub(crate) height: usize,
+}
+
+impl BigmapSampler<'_> {
+	/// Returns the average color of the map pixels in the given region.
+	/// The mip level is picked so that only a few texels are sampled per region.
+	pub(crate) fn average_color(&self, region: &PixelRect) -> [u8; 3] {
+		let (start_x, end_x) = (region.x, region.x + region.width);
+		let (start_y, end_y) = (region.y, region.y + region.height);
+		if end_x <= start_x || end_y <= start_y {
+			return [0, 0, 0];
+		}
+
+		let level = TileMipmaps::level_for_footprint((end_x - start_x).min(end_y - start_y));
+		let level_size = mip_level_size(level);
+		let texel_end = |end: usize| (end + (1 << level) - 1) >> level;
+
+		let mut color = [0u32; 3];
+		let mut texels_sampled = 0u32;
+
+		for texel_y in (start_y >> level)..texel_end(end_y) {
+			let cell_y = texel_y / level_size;
+			if cell_y >= self.height {
+				break;
+			}
+
+			for texel_x in (start_x >> level)..texel_end(end_x) {
+				let cell_x = texel_x / level_size;
+				if cell_x >= self.width {
+					break;
+				}
+
+				let Some(&tile_index) = self.bigmap.get(cell_y * self.width + cell_x) else {
+					continue;
+				};
+				let Some(texel) = self.mipmaps.texel(
+					tile_index as usize,
+					level,
+					texel_x % level_size,
+					texel_y % level_size,
+				) else {
+					continue;
+				};
+
+				color[0] += texel[0] as u32;
+				color[1] += texel[1] as u32;
+				color[2] += texel[2] as u32;
+				texels_sampled += 1;
+			}
+		}
+
+		if texels_sampled == 0 {
+			return [0, 0, 0];
+		}
+
+		[
+			(color[0] / texels_sampled) as u8,
+			(color[1] / texels_sampled) as u8,
+			(color[2] / texels_sampled) as u8,
+		]
+	}
 }
 
 
@@ -171,7 +383,7 @@ mod tests {
 	use crate::read_wrl_file;
 
 	#[test]
-	fn test_interpolate_pixel_color() {
+	fn test_generate_bigmap_preview_reference() {
 		run_test!({
 			// Arrange
 			let mut preview_ref = Vec::new();
@@ -180,17 +392,20 @@ mod tests {
 
 			let path = PathBuf::from("test_files/reference/REF.WRL");
 			let wrl = read_wrl_file(&path).unwrap();
+			let mipmaps = TileMipmaps::for_bigmap(&wrl.bigmap, &wrl.tiles, &wrl.palette);
 
 			// Act
 			let preview = generate_bigmap_preview(
 				&wrl.bigmap,
-				&wrl.tiles,
-				&wrl.palette,
+				&mipmaps,
+				&wrl.pass_table,
 				wrl.width,
 				wrl.height,
-				128,
-				128,
-				false,
+				PreviewOptions {
+					target_pixel_width: 128,
+					target_pixel_height: 128,
+					..PreviewOptions::default()
+				},
 			);
 
 			// Assert
@@ -200,4 +415,178 @@ mod tests {
 			assert!(logs.is_empty());
 		});
 	}
+
+	#[test]
+	fn test_generate_bigmap_preview_cell_colors() {
+		run_test!({
+			// Arrange
+			let path = PathBuf::from("test_files/reference/REF.WRL");
+			let wrl = read_wrl_file(&path).unwrap();
+			let mipmaps = TileMipmaps::for_bigmap(&wrl.bigmap, &wrl.tiles, &wrl.palette);
+
+			// Act
+			let preview = generate_bigmap_preview(
+				&wrl.bigmap, &mipmaps, &wrl.pass_table,
+				wrl.width, wrl.height, PreviewOptions { target_pixel_width: 16, target_pixel_height: 16, scanline: true, ..PreviewOptions::default() },
+			);
+
+			// Assert
+			// One preview pixel per cell, cell k uses tile (2k) % 64 filled with color (2k) % 64.
+			assert_eq!(preview.len(), 16 * 16 * 4);
+			for cell in [0, 1, 15, 16, 40, 255] {
+				let tile_color = ((2 * cell) % 64) as u8;
+				let expected = if (cell / 16) % 2 == 1 { tile_color / 2 } else { tile_color };
+				assert_eq!(preview[cell * 4..cell * 4 + 4], [expected, expected, expected, 255]);
+			}
+
+			let logs = get_captured_logs();
+			assert!(logs.is_empty());
+		});
+	}
+
+	#[test]
+	fn test_generate_bigmap_preview_passability_overlay() {
+		run_test!({
+			// Arrange
+			let path = PathBuf::from("test_files/reference/REF.WRL");
+			let mut wrl = read_wrl_file(&path).unwrap();
+			// Cell (1, 0) uses tile 2, cell (2, 0) uses tile 4.
+			wrl.pass_table[2] = 1;
+			wrl.pass_table[4] = 3;
+			wrl.pass_table[6] = 9;
+			let mipmaps = TileMipmaps::for_bigmap(&wrl.bigmap, &wrl.tiles, &wrl.palette);
+
+			// Act
+			let plain = generate_bigmap_preview(
+				&wrl.bigmap, &mipmaps, &wrl.pass_table,
+				wrl.width, wrl.height, PreviewOptions { target_pixel_width: 16, target_pixel_height: 16, ..PreviewOptions::default() },
+			);
+			let preview = generate_bigmap_preview(
+				&wrl.bigmap, &mipmaps, &wrl.pass_table,
+				wrl.width, wrl.height, PreviewOptions { target_pixel_width: 16, target_pixel_height: 16, overlay: PreviewOverlay::Passability, ..PreviewOptions::default() },
+			);
+
+			// Assert
+			let blend = |pixel: &[u8], tint: [u8; 3]| -> Vec<u8> {
+				(0..3)
+					.map(|channel| ((pixel[channel] as u16 + tint[channel] as u16) / 2) as u8)
+					.chain([255])
+					.collect()
+			};
+			let land = passability_tint_color(Passability::Land);
+			let water = passability_tint_color(Passability::Water);
+			let blocked = passability_tint_color(Passability::Blocked);
+			assert_eq!(preview[0..4], blend(&plain[0..4], land)[..]);
+			assert_eq!(preview[4..8], blend(&plain[4..8], water)[..]);
+			assert_eq!(preview[8..12], blend(&plain[8..12], blocked)[..]);
+			assert_eq!(preview[12..16], plain[12..16]);
+
+			let logs = get_captured_logs();
+			assert!(logs.is_empty());
+		});
+	}
+
+	#[test]
+	fn test_preview_fit_output_size() {
+		run_test!({
+			// Act & Assert
+			assert_eq!(PreviewFit::Fit.output_size(24, 12, 96, 96), (96, 48));
+			assert_eq!(PreviewFit::Fit.output_size(12, 24, 96, 96), (48, 96));
+			assert_eq!(PreviewFit::Fit.output_size(16, 16, 200, 100), (100, 100));
+			assert_eq!(PreviewFit::Fill.output_size(24, 12, 96, 96), (96, 96));
+			assert_eq!(PreviewFit::Letterbox.output_size(24, 12, 96, 96), (96, 96));
+			assert_eq!(PreviewFit::from_name("fill"), Some(PreviewFit::Fill));
+			assert_eq!(PreviewFit::from_name("stretch"), None);
+
+			let logs = get_captured_logs();
+			assert!(logs.is_empty());
+		});
+	}
+
+	#[test]
+	fn test_generate_bigmap_preview_non_square_fit() {
+		run_test!({
+			// Arrange
+			// 24x12 cells, top row uses tile 7, other rows 8 bands of tiles 0..7, tile k has color 32k.
+			let path = PathBuf::from("test_files/reference/REF_WIDE.WRL");
+			let wrl = read_wrl_file(&path).unwrap();
+			let mipmaps = TileMipmaps::for_bigmap(&wrl.bigmap, &wrl.tiles, &wrl.palette);
+
+			// Act
+			let preview = generate_bigmap_preview(
+				&wrl.bigmap, &mipmaps, &wrl.pass_table,
+				wrl.width, wrl.height, PreviewOptions { target_pixel_width: 96, target_pixel_height: 96, fit: PreviewFit::Fit, ..PreviewOptions::default() },
+			);
+
+			// Assert
+			// 96x48 pixels, 4 pixels per cell.
+			assert_eq!(preview.len(), 96 * 48 * 4);
+			let pixel = |x: usize, y: usize| preview[(y * 96 + x) * 4..(y * 96 + x) * 4 + 4].to_vec();
+			assert_eq!(pixel(0, 0), [224, 224, 224, 255]);
+			assert_eq!(pixel(0, 4), [0, 0, 0, 255]);
+			assert_eq!(pixel(12, 4), [32, 32, 32, 255]);
+			assert_eq!(pixel(95, 4), [224, 224, 224, 255]);
+			assert_eq!(pixel(47, 47), [96, 96, 96, 255]);
+
+			let logs = get_captured_logs();
+			assert!(logs.is_empty());
+		});
+	}
+
+	#[test]
+	fn test_generate_bigmap_preview_non_square_letterbox() {
+		run_test!({
+			// Arrange
+			let path = PathBuf::from("test_files/reference/REF_WIDE.WRL");
+			let wrl = read_wrl_file(&path).unwrap();
+			let mipmaps = TileMipmaps::for_bigmap(&wrl.bigmap, &wrl.tiles, &wrl.palette);
+
+			// Act
+			let preview = generate_bigmap_preview(
+				&wrl.bigmap, &mipmaps, &wrl.pass_table,
+				wrl.width, wrl.height, PreviewOptions { target_pixel_width: 96, target_pixel_height: 96, ..PreviewOptions::default() },
+			);
+
+			// Assert
+			// The 96x48 map is centered between black bars of 24 rows.
+			assert_eq!(preview.len(), 96 * 96 * 4);
+			let pixel = |x: usize, y: usize| preview[(y * 96 + x) * 4..(y * 96 + x) * 4 + 4].to_vec();
+			assert_eq!(pixel(47, 0), [0, 0, 0, 255]);
+			assert_eq!(pixel(47, 23), [0, 0, 0, 255]);
+			assert_eq!(pixel(47, 24), [224, 224, 224, 255]);
+			assert_eq!(pixel(12, 28), [32, 32, 32, 255]);
+			assert_eq!(pixel(47, 71), [96, 96, 96, 255]);
+			assert_eq!(pixel(47, 72), [0, 0, 0, 255]);
+
+			let logs = get_captured_logs();
+			assert!(logs.is_empty());
+		});
+	}
+
+	#[test]
+	fn test_generate_bigmap_preview_non_square_fill() {
+		run_test!({
+			// Arrange
+			let path = PathBuf::from("test_files/reference/REF_WIDE.WRL");
+			let wrl = read_wrl_file(&path).unwrap();
+			let mipmaps = TileMipmaps::for_bigmap(&wrl.bigmap, &wrl.tiles, &wrl.palette);
+
+			// Act
+			let preview = generate_bigmap_preview(
+				&wrl.bigmap, &mipmaps, &wrl.pass_table,
+				wrl.width, wrl.height, PreviewOptions { target_pixel_width: 48, target_pixel_height: 48, fit: PreviewFit::Fill, ..PreviewOptions::default() },
+			);
+
+			// Assert
+			// Only the 12x12 cells in the middle of the map are shown, cells 6 to 17.
+			assert_eq!(preview.len(), 48 * 48 * 4);
+			let pixel = |x: usize, y: usize| preview[(y * 48 + x) * 4..(y * 48 + x) * 4 + 4].to_vec();
+			assert_eq!(pixel(0, 0), [224, 224, 224, 255]);
+			assert_eq!(pixel(0, 47), [64, 64, 64, 255]);
+			assert_eq!(pixel(47, 47), [160, 160, 160, 255]);
+
+			let logs = get_captured_logs();
+			assert!(logs.is_empty());
+		});
+	}
 }
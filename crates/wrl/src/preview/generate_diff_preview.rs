@@ -0,0 +1,70 @@
+use crate::ops::MapDiff;
+use crate::structs::WRLFile;
+
+/// Color used to highlight changed cells in the diff preview.
+pub const DIFF_HIGHLIGHT_COLOR: [u8; 3] = [255, 0, 0];
+
+/// Generate an RGBA pixel buffer with one pixel per cell of the new map.
+/// Unchanged cells show the dimmed minimap color, changed cells are highlighted.
+/// The width and height are those of the new map.
+pub fn generate_diff_preview(new_wrl: &WRLFile, diff: &MapDiff) -> Vec<u8> {
+	let width = new_wrl.width as usize;
+	let height = new_wrl.height as usize;
+
+	let mut preview = Vec::with_capacity(width * height * 4);
+	for &color_index in new_wrl.minimap.iter().take(width * height) {
+		let base_index = (color_index as usize) * 3;
+		preview.push(new_wrl.palette[base_index] / 3);
+		preview.push(new_wrl.palette[base_index + 1] / 3);
+		preview.push(new_wrl.palette[base_index + 2] / 3);
+		preview.push(255);
+	}
+
+	for cell in &diff.changed_cells {
+		let (x, y) = (cell.x as usize, cell.y as usize);
+		if x >= width || y >= height {
+			continue;
+		}
+
+		let index = (y * width + x) * 4;
+		preview[index..index + 3].copy_from_slice(&DIFF_HIGHLIGHT_COLOR);
+	}
+
+	preview
+}
+
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use std::path::PathBuf;
+
+	use crate::run_test;
+	use crate::{diff_maps, read_wrl_file, TILE_DATA_SIZE};
+
+	#[test]
+	fn test_generate_diff_preview() {
+		run_test!({
+			// Arrange
+			let old_wrl = read_wrl_file(&PathBuf::from("test_files/reference/REF.WRL")).unwrap();
+			let mut new_wrl = old_wrl.clone();
+			// Only cells (1, 0), (1, 2), ... use tile 2.
+			new_wrl.tiles[2 * TILE_DATA_SIZE] = 200;
+			let diff = diff_maps(&old_wrl, &new_wrl).unwrap();
+
+			// Act
+			let preview = generate_diff_preview(&new_wrl, &diff);
+
+			// Assert
+			assert_eq!(preview.len(), 16 * 16 * 4);
+			// Cell (0, 1) stores minimap color 16, which is dimmed.
+			assert_eq!(preview[64..68], [5, 5, 5, 255]);
+			assert_eq!(preview[4..8], [255, 0, 0, 255]);
+			let highlighted = preview.chunks_exact(4).filter(|pixel| pixel[0..3] == DIFF_HIGHLIGHT_COLOR).count();
+			assert_eq!(highlighted, 8);
+
+			let logs = get_captured_logs();
+			assert!(logs.is_empty());
+		});
+	}
+}
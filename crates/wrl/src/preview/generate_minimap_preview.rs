@@ -21,8 +21,10 @@ pub fn generate_minimap_preview(
 #[cfg(test)]
 mod tests {
 	use super::*;
+	use std::path::PathBuf;
 
 	use crate::run_test;
+	use crate::read_wrl_header;
 
 	#[test]
 	fn test_generate_minimap_preview() {
@@ -52,4 +54,27 @@ mod tests {
 			assert!(logs.is_empty());
 		});
 	}
+
+	#[test]
+	fn test_generate_minimap_preview_non_square() {
+		run_test!({
+			// Arrange
+			let path = PathBuf::from("test_files/reference/REF_WIDE.WRL");
+			let header = read_wrl_header(&path).unwrap();
+
+			// Act
+			let preview = generate_minimap_preview(&header.minimap, &header.palette, header.width, header.height);
+
+			// Assert
+			// Rows are 24 cells wide, the top row uses tile 7, other rows 8 bands of 3 cells.
+			assert_eq!(preview.len(), 24 * 12 * 4);
+			let pixel = |x: usize, y: usize| preview[(y * 24 + x) * 4..(y * 24 + x) * 4 + 4].to_vec();
+			assert_eq!(pixel(0, 0), [224, 224, 224, 255]);
+			assert_eq!(pixel(5, 11), [32, 32, 32, 255]);
+			assert_eq!(pixel(23, 11), [224, 224, 224, 255]);
+
+			let logs = get_captured_logs();
+			assert!(logs.is_empty());
+		});
+	}
 }
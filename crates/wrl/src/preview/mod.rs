@@ -1,11 +1,29 @@
+pub mod cycle_palette;
+pub use cycle_palette::*;
+
+pub mod generate_bigmap_animation;
+pub use generate_bigmap_animation::*;
+
 pub mod generate_bigmap_preview;
 pub use generate_bigmap_preview::*;
 
+pub mod generate_diff_preview;
+pub use generate_diff_preview::*;
+
 pub mod generate_minimap_preview;
 pub use generate_minimap_preview::*;
 
 pub mod save_bigmap_to_file;
 pub use save_bigmap_to_file::*;
 
+pub mod save_full_map_to_file;
+pub use save_full_map_to_file::*;
+
 pub mod save_minimap_to_file;
 pub use save_minimap_to_file::*;
+
+pub mod save_tile_atlas_to_file;
+pub use save_tile_atlas_to_file::*;
+
+pub mod tile_mipmaps;
+pub use tile_mipmaps::*;
@@ -1,47 +1,52 @@
 use std::path::Path;
 use image::{ImageBuffer, Rgba};
 
-use crate::read_wrl_file::read_wrl_file;
-use crate::generate_bigmap_preview::generate_bigmap_preview;
+use crate::error::WrlError;
+use crate::file::wrl_view::WrlView;
+use crate::generate_bigmap_preview::{generate_bigmap_preview, PreviewOptions};
+use crate::tile_mipmaps::TileMipmaps;
 
 
 pub fn save_bigmap_to_file(
 	wrl_file: &Path,
 	png_output_file: &Path,
-	target_width: usize,
-	target_height: usize,
-	scanline: bool,
-) -> Result<(), ()> {
-	let header = match read_wrl_file(wrl_file) {
-		Ok(header) => header,
-		Err(_) => {
+	options: PreviewOptions,
+) -> Result<(), WrlError> {
+	let wrl_view = match WrlView::open(wrl_file) {
+		Ok(view) => view,
+		Err(e) => {
 			log::error!("failed to read WRL header for file {}", wrl_file.display());
-			return Err(());
+			return Err(e);
 		}
 	};
 
-	let bigmap = header.bigmap;
-	let tiles = header.tiles;
-	let palette = header.palette;
-	let width = header.width;
-	let height = header.height;
+	let pass_table = match wrl_view.pass_table() {
+		Ok(pass_table) => pass_table,
+		Err(e) => {
+			log::error!("failed to read pass table for file {}", wrl_file.display());
+			return Err(e);
+		}
+	};
+	let width = wrl_view.width();
+	let height = wrl_view.height();
 
+	let bigmap = wrl_view.bigmap();
+	let mipmaps = TileMipmaps::for_bigmap(&bigmap, wrl_view.tiles(), wrl_view.palette());
 	let preview = generate_bigmap_preview(
 		&bigmap,
-		&tiles,
-		&palette,
+		&mipmaps,
+		pass_table,
 		width,
 		height,
-		target_width,
-		target_height,
-		scanline,
+		options,
 	);
+	let (output_width, output_height) = options.output_size(width, height);
 
-	let img: ImageBuffer<Rgba<u8>, Vec<u8>> = match ImageBuffer::from_raw(target_width as u32, target_height as u32, preview) {
+	let img: ImageBuffer<Rgba<u8>, Vec<u8>> = match ImageBuffer::from_raw(output_width as u32, output_height as u32, preview) {
 		Some(img) => img,
 		None => {
 			log::error!("failed to create image buffer");
-			return Err(());
+			return Err(WrlError::Image("failed to create image buffer".to_string()));
 		}
 	};
 
@@ -49,7 +54,7 @@ pub fn save_bigmap_to_file(
 		Ok(_) => Ok(()),
 		Err(e) => {
 			log::error!("failed to save image for file {}: {}", wrl_file.display(), e);
-			Err(())
+			Err(WrlError::Image(e.to_string()))
 		}
 	}
 }
@@ -74,7 +79,11 @@ mod tests {
 			let png_output_file = PathBuf::from("test_files/temp/bigmap_preview.png");
 
 			// Act
-			let result = save_bigmap_to_file(&wrl_file, &png_output_file, 100, 100, false);
+			let result = save_bigmap_to_file(&wrl_file, &png_output_file, PreviewOptions {
+				target_pixel_width: 100,
+				target_pixel_height: 100,
+				..PreviewOptions::default()
+			});
 
 			// Assert
 			assert!(result.is_ok());
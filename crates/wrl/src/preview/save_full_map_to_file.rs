@@ -0,0 +1,144 @@
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::Path;
+
+use crate::consts::{TILE_DATA_SIZE, TILE_SIZE};
+use crate::error::WrlError;
+use crate::read_wrl_file::read_wrl_file;
+use crate::structs::WRLFile;
+
+
+/// Renders the whole map at 1:1 scale (64x64 pixels per cell) as RGB PNG.
+/// The image is encoded one row of cells at a time, so only a single band
+/// of pixels is kept in memory.
+pub fn write_full_map_png<W: Write>(wrl_file: &WRLFile, writer: W) -> Result<(), WrlError> {
+	wrl_file.check_sections_size()?;
+	wrl_file.check_tile_indices()?;
+
+	let width = wrl_file.width as usize;
+	let height = wrl_file.height as usize;
+	let pixel_width = width * TILE_SIZE;
+	let pixel_height = height * TILE_SIZE;
+
+	let mut encoder = png::Encoder::new(writer, pixel_width as u32, pixel_height as u32);
+	encoder.set_color(png::ColorType::Rgb);
+	encoder.set_depth(png::BitDepth::Eight);
+
+	let mut png_writer = encoder.write_header().map_err(|e| WrlError::Image(e.to_string()))?;
+	let mut stream = png_writer.stream_writer().map_err(|e| WrlError::Image(e.to_string()))?;
+
+	let mut band = vec![0u8; pixel_width * TILE_SIZE * 3];
+
+	for cell_y in 0..height {
+		for cell_x in 0..width {
+			let tile_index = wrl_file.bigmap[cell_y * width + cell_x] as usize;
+			let tile = &wrl_file.tiles[tile_index * TILE_DATA_SIZE..(tile_index + 1) * TILE_DATA_SIZE];
+
+			for (tile_y, tile_row) in tile.chunks_exact(TILE_SIZE).enumerate() {
+				let band_row_start = (tile_y * pixel_width + cell_x * TILE_SIZE) * 3;
+				let band_row = &mut band[band_row_start..band_row_start + TILE_SIZE * 3];
+
+				for (pixel, &color_index) in band_row.chunks_exact_mut(3).zip(tile_row) {
+					let palette_index = color_index as usize * 3;
+					pixel.copy_from_slice(&wrl_file.palette[palette_index..palette_index + 3]);
+				}
+			}
+		}
+
+		stream.write_all(&band)?;
+	}
+
+	stream.finish().map_err(|e| WrlError::Image(e.to_string()))?;
+	png_writer.finish().map_err(|e| WrlError::Image(e.to_string()))
+}
+
+/// Read WRL file and save the full-resolution map image as PNG file.
+pub fn save_full_map_to_file(
+	wrl_file: &Path,
+	png_output_file: &Path,
+) -> Result<(), WrlError> {
+	let wrl = match read_wrl_file(wrl_file) {
+		Ok(wrl) => wrl,
+		Err(e) => {
+			log::error!("failed to read WRL file {}", wrl_file.display());
+			return Err(e);
+		}
+	};
+
+	let file = match File::create(png_output_file) {
+		Ok(file) => file,
+		Err(e) => {
+			log::error!("failed to create file {}: {}", png_output_file.display(), e);
+			return Err(e.into());
+		}
+	};
+
+	let mut writer = BufWriter::new(file);
+	write_full_map_png(&wrl, &mut writer)
+		.and_then(|_| writer.flush().map_err(WrlError::from))
+		.inspect_err(|e| {
+			log::error!("failed to save image for file {}: {}", wrl_file.display(), e);
+		})
+}
+
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use std::path::PathBuf;
+
+	use crate::run_test;
+
+	#[test]
+	fn test_write_full_map_png() {
+		run_test!({
+			// Arrange
+			let wrl_file = read_wrl_file(&PathBuf::from("test_files/reference/REF.WRL")).unwrap();
+			let mut png_data = Vec::new();
+
+			// Act
+			let result = write_full_map_png(&wrl_file, &mut png_data);
+
+			// Assert
+			assert!(result.is_ok());
+
+			let img = image::load_from_memory(&png_data).unwrap().to_rgb8();
+			assert_eq!(img.dimensions(), (1024, 1024));
+			// Cell k uses tile (2 * k) % 64, which is filled with palette color (i, i, i).
+			assert_eq!(img.get_pixel(0, 0).0, [0, 0, 0]);
+			assert_eq!(img.get_pixel(127, 63).0, [2, 2, 2]);
+			assert_eq!(img.get_pixel(64, 64).0, [34, 34, 34]);
+			assert_eq!(img.get_pixel(1023, 1023).0, [62, 62, 62]);
+
+			let logs = get_captured_logs();
+			assert!(logs.is_empty());
+		});
+	}
+
+	#[test]
+	fn test_save_full_map_to_file() {
+		run_test!({
+			// Arrange
+			std::fs::create_dir_all("test_files/temp").expect("Failed to create temp directory");
+			let wrl_file = PathBuf::from("test_files/reference/REF.WRL");
+			let png_output_file = PathBuf::from("test_files/temp/full_map.png");
+
+			// Act
+			let result = save_full_map_to_file(&wrl_file, &png_output_file);
+
+			// Assert
+			assert!(result.is_ok());
+
+			let img = image::open(&png_output_file).unwrap();
+			assert_eq!((img.width(), img.height()), (1024, 1024));
+
+			let logs = get_captured_logs();
+			assert!(logs.is_empty());
+
+			// Clean up the output file
+			if let Err(e) = std::fs::remove_file(&png_output_file) {
+				log::error!("Failed to remove output file after test: {}", e);
+			}
+		});
+	}
+}
@@ -1,6 +1,7 @@
 use std::path::Path;
 use image::{ImageBuffer, Rgba};
 
+use crate::error::WrlError;
 use crate::read_wrl_header::read_wrl_header;
 use crate::generate_minimap_preview::generate_minimap_preview;
 
@@ -9,12 +10,12 @@ use crate::generate_minimap_preview::generate_minimap_preview;
 pub fn save_minimap_to_file(
 	wrl_file: &Path,
 	png_output_file: &Path,
-) -> Result<(), ()> {
+) -> Result<(), WrlError> {
 	let header = match read_wrl_header(wrl_file) {
 		Ok(header) => header,
-		Err(_) => {
+		Err(e) => {
 			log::error!("failed to read WRL header for file {}", wrl_file.display());
-			return Err(());
+			return Err(e);
 		}
 	};
 
@@ -29,7 +30,7 @@ pub fn save_minimap_to_file(
 		Some(img) => img,
 		None => {
 			log::error!("failed to create image buffer");
-			return Err(());
+			return Err(WrlError::Image("failed to create image buffer".to_string()));
 		}
 	};
 
@@ -37,7 +38,7 @@ pub fn save_minimap_to_file(
 		Ok(_) => Ok(()),
 		Err(e) => {
 			log::error!("failed to save image for file {}: {}", wrl_file.display(), e);
-			Err(())
+			Err(WrlError::Image(e.to_string()))
 		}
 	}
 }
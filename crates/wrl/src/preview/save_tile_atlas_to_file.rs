@@ -0,0 +1,236 @@
+use std::path::Path;
+
+use image::{ImageBuffer, Rgba};
+use serde::Serialize;
+
+use crate::consts::{TILE_DATA_SIZE, TILE_SIZE};
+use crate::error::WrlError;
+use crate::read_wrl_file::read_wrl_file;
+use crate::structs::WRLFile;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct TileAtlasEntry {
+	pub tile_index: u16,
+	pub pass_value: u8,
+	/// Number of bigmap cells using the tile.
+	pub usage_count: usize,
+	/// Position of the tile in the atlas, in pixels.
+	pub x: u32,
+	pub y: u32,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct TileAtlasMetadata {
+	pub tile_size: u32,
+	pub columns: u32,
+	pub rows: u32,
+	pub tiles: Vec<TileAtlasEntry>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct TileAtlas {
+	pub width: u32,
+	pub height: u32,
+	/// RGBA pixel buffer.
+	pub pixels: Vec<u8>,
+	pub metadata: TileAtlasMetadata,
+}
+
+/// Lays out all tiles of the map in a grid, ordered by tile index.
+/// Without a column count, the grid is as close to a square as possible.
+pub fn generate_tile_atlas(wrl_file: &WRLFile, columns: Option<u32>) -> Result<TileAtlas, WrlError> {
+	wrl_file.check_sections_size()?;
+
+	let tile_count = wrl_file.tile_count as u32;
+	if tile_count == 0 {
+		return Err(WrlError::Image("Map has no tiles".to_string()));
+	}
+
+	let columns = columns
+		.unwrap_or_else(|| (tile_count as f64).sqrt().ceil() as u32)
+		.clamp(1, tile_count);
+	let rows = tile_count.div_ceil(columns);
+	let width = columns * TILE_SIZE as u32;
+	let height = rows * TILE_SIZE as u32;
+
+	let mut usage_counts = vec![0usize; tile_count as usize];
+	for &tile_index in &wrl_file.bigmap {
+		if let Some(usage_count) = usage_counts.get_mut(tile_index as usize) {
+			*usage_count += 1;
+		}
+	}
+
+	let mut pixels = vec![0u8; width as usize * height as usize * 4];
+	let mut tiles = Vec::with_capacity(tile_count as usize);
+
+	for (tile_index, tile) in wrl_file.tiles.chunks_exact(TILE_DATA_SIZE).enumerate() {
+		let x = (tile_index as u32 % columns) * TILE_SIZE as u32;
+		let y = (tile_index as u32 / columns) * TILE_SIZE as u32;
+
+		for (tile_y, tile_row) in tile.chunks_exact(TILE_SIZE).enumerate() {
+			let row_start = ((y as usize + tile_y) * width as usize + x as usize) * 4;
+			let row = &mut pixels[row_start..row_start + TILE_SIZE * 4];
+
+			for (pixel, &color_index) in row.chunks_exact_mut(4).zip(tile_row) {
+				let palette_index = color_index as usize * 3;
+				pixel[0..3].copy_from_slice(&wrl_file.palette[palette_index..palette_index + 3]);
+				pixel[3] = 255;
+			}
+		}
+
+		tiles.push(TileAtlasEntry {
+			tile_index: tile_index as u16,
+			pass_value: wrl_file.pass_table[tile_index],
+			usage_count: usage_counts[tile_index],
+			x,
+			y,
+		});
+	}
+
+	Ok(TileAtlas {
+		width,
+		height,
+		pixels,
+		metadata: TileAtlasMetadata {
+			tile_size: TILE_SIZE as u32,
+			columns,
+			rows,
+			tiles,
+		},
+	})
+}
+
+/// Read WRL file and save its tile atlas as PNG file.
+/// The tile metadata is saved next to the image as JSON file with the same name.
+pub fn save_tile_atlas_to_file(
+	wrl_file: &Path,
+	png_output_file: &Path,
+	columns: Option<u32>,
+) -> Result<TileAtlasMetadata, WrlError> {
+	let wrl = match read_wrl_file(wrl_file) {
+		Ok(wrl) => wrl,
+		Err(e) => {
+			log::error!("failed to read WRL file {}", wrl_file.display());
+			return Err(e);
+		}
+	};
+
+	let atlas = generate_tile_atlas(&wrl, columns).inspect_err(|e| {
+		log::error!("failed to generate tile atlas for file {}: {}", wrl_file.display(), e);
+	})?;
+
+	let img: ImageBuffer<Rgba<u8>, Vec<u8>> = match ImageBuffer::from_raw(atlas.width, atlas.height, atlas.pixels) {
+		Some(img) => img,
+		None => {
+			log::error!("failed to create image buffer");
+			return Err(WrlError::Image("failed to create image buffer".to_string()));
+		}
+	};
+
+	if let Err(e) = img.save_with_format(png_output_file, image::ImageFormat::Png) {
+		log::error!("failed to save image for file {}: {}", wrl_file.display(), e);
+		return Err(WrlError::Image(e.to_string()));
+	}
+
+	let json_output_file = png_output_file.with_extension("json");
+	let json = serde_json::to_string_pretty(&atlas.metadata).map_err(|e| {
+		log::error!("failed to serialize tile atlas metadata: {}", e);
+		WrlError::Serialization(e.to_string())
+	})?;
+
+	if let Err(e) = std::fs::write(&json_output_file, json) {
+		log::error!("failed to save tile atlas metadata {}: {}", json_output_file.display(), e);
+		return Err(e.into());
+	}
+
+	Ok(atlas.metadata)
+}
+
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use std::path::PathBuf;
+
+	use crate::run_test;
+
+	#[test]
+	fn test_generate_tile_atlas() {
+		run_test!({
+			// Arrange
+			let mut wrl_file = read_wrl_file(&PathBuf::from("test_files/reference/REF.WRL")).unwrap();
+			wrl_file.pass_table[5] = 3;
+
+			// Act
+			let atlas = generate_tile_atlas(&wrl_file, None).unwrap();
+
+			// Assert
+			assert_eq!((atlas.width, atlas.height), (512, 512));
+			assert_eq!((atlas.metadata.columns, atlas.metadata.rows), (8, 8));
+			assert_eq!(atlas.metadata.tiles.len(), 64);
+			// Only even tiles are used, each by 8 of the 256 cells.
+			assert_eq!(atlas.metadata.tiles[4], TileAtlasEntry { tile_index: 4, pass_value: 0, usage_count: 8, x: 256, y: 0 });
+			assert_eq!(atlas.metadata.tiles[5], TileAtlasEntry { tile_index: 5, pass_value: 3, usage_count: 0, x: 320, y: 0 });
+			assert_eq!(atlas.metadata.tiles[9].y, 64);
+
+			// Tile 9 is filled with palette color (9, 9, 9).
+			let pixel_index = (64 * 512 + 64) * 4;
+			assert_eq!(atlas.pixels[pixel_index..pixel_index + 4], [9, 9, 9, 255]);
+
+			let logs = get_captured_logs();
+			assert!(logs.is_empty());
+		});
+	}
+
+	#[test]
+	fn test_generate_tile_atlas_columns() {
+		run_test!({
+			// Arrange
+			let wrl_file = read_wrl_file(&PathBuf::from("test_files/reference/REF.WRL")).unwrap();
+
+			// Act
+			let atlas = generate_tile_atlas(&wrl_file, Some(10)).unwrap();
+
+			// Assert
+			assert_eq!((atlas.width, atlas.height), (640, 448));
+			assert_eq!((atlas.metadata.columns, atlas.metadata.rows), (10, 7));
+
+			let logs = get_captured_logs();
+			assert!(logs.is_empty());
+		});
+	}
+
+	#[test]
+	fn test_save_tile_atlas_to_file() {
+		run_test!({
+			// Arrange
+			std::fs::create_dir_all("test_files/temp").expect("Failed to create temp directory");
+			let wrl_file = PathBuf::from("test_files/reference/REF.WRL");
+			let png_output_file = PathBuf::from("test_files/temp/tile_atlas.png");
+			let json_output_file = PathBuf::from("test_files/temp/tile_atlas.json");
+
+			// Act
+			let result = save_tile_atlas_to_file(&wrl_file, &png_output_file, None);
+
+			// Assert
+			assert!(result.is_ok());
+
+			let img = image::open(&png_output_file).unwrap();
+			assert_eq!((img.width(), img.height()), (512, 512));
+
+			let json: serde_json::Value = serde_json::from_str(&std::fs::read_to_string(&json_output_file).unwrap()).unwrap();
+			assert_eq!(json["tiles"][2]["usage_count"], 8);
+			assert_eq!(json["tiles"][2]["pass_value"], 0);
+
+			let logs = get_captured_logs();
+			assert!(logs.is_empty());
+
+			// Clean up the output files
+			for file_path in [&png_output_file, &json_output_file] {
+				if let Err(e) = std::fs::remove_file(file_path) {
+					log::error!("Failed to remove output file after test: {}", e);
+				}
+			}
+		});
+	}
+}
@@ -0,0 +1,174 @@
+use rayon::prelude::*;
+
+use crate::consts::{TILE_DATA_SIZE, TILE_SIZE};
+
+/// Number of mip levels of a tile, from full 64x64 resolution down to a single texel.
+pub const TILE_MIP_LEVELS: usize = 7;
+
+/// Downscaled copies of the tiles, resolved through the palette.
+/// Level 0 holds the tile pixels, every next level halves the resolution,
+/// so the last level holds the average color of the whole tile.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TileMipmaps {
+	/// Mip chain of every tile, all levels stored one after another.
+	/// Tiles which were not built have an empty chain.
+	tiles: Vec<Vec<[u8; 3]>>,
+}
+
+impl TileMipmaps {
+	/// Computes the mip chains of all tiles in parallel.
+	/// Pixels referencing colors missing from the palette are left out of the averages.
+	pub fn new(tiles: &[u8], palette: &[u8]) -> TileMipmaps {
+		let tiles = tiles
+			.par_chunks_exact(TILE_DATA_SIZE)
+			.map(|tile| build_mip_chain(tile, palette))
+			.collect();
+
+		TileMipmaps { tiles }
+	}
+
+	/// Computes the mip chains of the tiles referenced by the bigmap only.
+	/// Texels of the other tiles are reported as missing.
+	pub fn for_bigmap(bigmap: &[u16], tiles: &[u8], palette: &[u8]) -> TileMipmaps {
+		let mut used_tiles = vec![false; tiles.len() / TILE_DATA_SIZE];
+		for &tile_index in bigmap {
+			if let Some(used) = used_tiles.get_mut(tile_index as usize) {
+				*used = true;
+			}
+		}
+
+		let tiles = tiles
+			.par_chunks_exact(TILE_DATA_SIZE)
+			.zip(used_tiles)
+			.map(|(tile, used)| if used { build_mip_chain(tile, palette) } else { Vec::new() })
+			.collect();
+
+		TileMipmaps { tiles }
+	}
+
+	pub fn tile_count(&self) -> usize {
+		self.tiles.len()
+	}
+
+	/// Returns the texel of the tile at the given mip level.
+	/// The coordinates are in texels of that level.
+	pub fn texel(&self, tile_index: usize, level: usize, x: usize, y: usize) -> Option<[u8; 3]> {
+		let level_size = mip_level_size(level);
+		if level >= TILE_MIP_LEVELS || x >= level_size || y >= level_size {
+			return None;
+		}
+
+		let mip_chain = self.tiles.get(tile_index)?;
+		mip_chain.get(mip_level_offset(level) + y * level_size + x).copied()
+	}
+
+	/// Returns the average color of the whole tile.
+	pub fn tile_average(&self, tile_index: usize) -> Option<[u8; 3]> {
+		self.texel(tile_index, TILE_MIP_LEVELS - 1, 0, 0)
+	}
+
+	/// Returns the most detailed mip level whose texels are not larger than the given footprint in pixels.
+	pub fn level_for_footprint(footprint: usize) -> usize {
+		let footprint = footprint.max(1);
+		((usize::BITS - 1 - footprint.leading_zeros()) as usize).min(TILE_MIP_LEVELS - 1)
+	}
+}
+
+/// Width and height of the mip level in texels.
+pub fn mip_level_size(level: usize) -> usize {
+	TILE_SIZE >> level.min(TILE_MIP_LEVELS - 1)
+}
+
+fn mip_level_offset(level: usize) -> usize {
+	(0..level).map(|level| mip_level_size(level) * mip_level_size(level)).sum()
+}
+
+fn build_mip_chain(tile: &[u8], palette: &[u8]) -> Vec<[u8; 3]> {
+	let mut mip_chain = Vec::with_capacity(mip_level_offset(TILE_MIP_LEVELS));
+
+	for level in 0..TILE_MIP_LEVELS {
+		let level_size = mip_level_size(level);
+		let texel_size = TILE_SIZE / level_size;
+
+		for texel_y in 0..level_size {
+			for texel_x in 0..level_size {
+				let mut color = [0u32; 3];
+				let mut pixel_count = 0u32;
+
+				for y in texel_y * texel_size..(texel_y + 1) * texel_size {
+					for x in texel_x * texel_size..(texel_x + 1) * texel_size {
+						let palette_index = tile[y * TILE_SIZE + x] as usize * 3;
+						if palette_index + 2 >= palette.len() {
+							continue;
+						}
+						color[0] += palette[palette_index] as u32;
+						color[1] += palette[palette_index + 1] as u32;
+						color[2] += palette[palette_index + 2] as u32;
+						pixel_count += 1;
+					}
+				}
+
+				mip_chain.push(color.map(|channel| channel.checked_div(pixel_count).unwrap_or(0) as u8));
+			}
+		}
+	}
+
+	mip_chain
+}
+
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use std::path::PathBuf;
+
+	use crate::run_test;
+	use crate::{calc_tile_average_color, read_wrl_file};
+
+	#[test]
+	fn test_tile_mipmaps() {
+		run_test!({
+			// Arrange
+			let path = PathBuf::from("test_files/reference/REF.WRL");
+			let mut wrl_file = read_wrl_file(&path).unwrap();
+			// Checkerboard of colors 0 and 2 in tile 0, every 2x2 block averages to 1.
+			for y in 0..TILE_SIZE {
+				for x in 0..TILE_SIZE {
+					wrl_file.tiles[y * TILE_SIZE + x] = if (x + y) % 2 == 0 { 0 } else { 2 };
+				}
+			}
+
+			// Act
+			let mipmaps = TileMipmaps::new(&wrl_file.tiles, &wrl_file.palette);
+
+			// Assert
+			assert_eq!(mipmaps.tile_count(), 64);
+			assert_eq!(mipmaps.texel(0, 0, 0, 0), Some([0, 0, 0]));
+			assert_eq!(mipmaps.texel(0, 0, 1, 0), Some([2, 2, 2]));
+			assert_eq!(mipmaps.texel(0, 1, 31, 31), Some([1, 1, 1]));
+			assert_eq!(mipmaps.texel(0, 1, 32, 0), None);
+			assert_eq!(mipmaps.tile_average(0), Some([1, 1, 1]));
+			for tile_index in 1..64 {
+				let expected = calc_tile_average_color(&wrl_file.tiles, &wrl_file.palette, tile_index);
+				assert_eq!(mipmaps.tile_average(tile_index), Some(expected));
+			}
+			assert_eq!(mipmaps.tile_average(64), None);
+
+			let bigmap_mipmaps = TileMipmaps::for_bigmap(&wrl_file.bigmap, &wrl_file.tiles, &wrl_file.palette);
+			assert_eq!(bigmap_mipmaps.tile_count(), 64);
+			assert_eq!(bigmap_mipmaps.tile_average(0), Some([1, 1, 1]));
+			assert_eq!(bigmap_mipmaps.tile_average(2), mipmaps.tile_average(2));
+			// The reference bigmap only references the even tiles.
+			assert_eq!(bigmap_mipmaps.tile_average(1), None);
+			assert_eq!(bigmap_mipmaps.texel(1, 0, 0, 0), None);
+
+			assert_eq!(TileMipmaps::level_for_footprint(0), 0);
+			assert_eq!(TileMipmaps::level_for_footprint(3), 1);
+			assert_eq!(TileMipmaps::level_for_footprint(56), 5);
+			assert_eq!(TileMipmaps::level_for_footprint(1000), 6);
+
+			let logs = get_captured_logs();
+			assert!(logs.is_empty());
+		});
+	}
+}
@@ -1,8 +1,60 @@
 use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Deserialize, Serialize)]
+use crate::consts::{TILE_DATA_SIZE, WRL_FORMAT_VERSION, WRL_MAGIC};
+use crate::error::{WrlError, WrlSection};
+
+/// Decoded 5-byte header at the start of every WRL file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+pub struct WRLFileHeader {
+	pub magic: [u8; 3],
+	pub version: u16,
+}
+
+impl Default for WRLFileHeader {
+	fn default() -> Self {
+		WRLFileHeader {
+			magic: WRL_MAGIC,
+			version: WRL_FORMAT_VERSION,
+		}
+	}
+}
+
+impl WRLFileHeader {
+	/// Size of the header on disk.
+	pub const SIZE: usize = 5;
+
+	/// Decodes the header bytes, rejecting files which are not WRL files or use an unknown format version.
+	pub fn from_bytes(bytes: &[u8]) -> Result<WRLFileHeader, WrlError> {
+		if bytes.len() < WRLFileHeader::SIZE {
+			return Err(WrlError::Truncated {
+				section: WrlSection::Header,
+				expected: WRLFileHeader::SIZE as u64,
+				found: bytes.len() as u64,
+			});
+		}
+
+		let magic = [bytes[0], bytes[1], bytes[2]];
+		if magic != WRL_MAGIC {
+			return Err(WrlError::InvalidMagic { found: magic });
+		}
+
+		let version = u16::from_le_bytes([bytes[3], bytes[4]]);
+		if version != WRL_FORMAT_VERSION {
+			return Err(WrlError::UnsupportedVersion { version });
+		}
+
+		Ok(WRLFileHeader { magic, version })
+	}
+
+	pub fn to_bytes(&self) -> [u8; 5] {
+		let version = self.version.to_le_bytes();
+		[self.magic[0], self.magic[1], self.magic[2], version[0], version[1]]
+	}
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
 pub struct WRLFile {
-	pub header: Vec<u8>,
+	pub header: WRLFileHeader,
 	pub width: u16,
 	pub height: u16,
 	pub minimap: Vec<u8>,
@@ -13,8 +65,53 @@ pub struct WRLFile {
 	pub pass_table: Vec<u8>,
 }
 
+impl WRLFile {
+	/// Checks that every section length matches the dimensions and tile count.
+	pub fn check_sections_size(&self) -> Result<(), WrlError> {
+		let cell_count = self.width as usize * self.height as usize;
+		let tile_count = self.tile_count as usize;
+
+		let sections = [
+			(WrlSection::Minimap, self.minimap.len(), cell_count),
+			(WrlSection::Bigmap, self.bigmap.len(), cell_count),
+			(WrlSection::Tiles, self.tiles.len(), tile_count * TILE_DATA_SIZE),
+			(WrlSection::Palette, self.palette.len(), 256 * 3),
+			(WrlSection::PassTable, self.pass_table.len(), tile_count),
+		];
+
+		for (section, actual_size, expected_size) in sections {
+			if actual_size != expected_size {
+				return Err(WrlError::InvalidSectionSize {
+					section,
+					expected: expected_size as u64,
+					found: actual_size as u64,
+				});
+			}
+		}
+
+		Ok(())
+	}
+
+	/// Checks that every bigmap cell references an existing tile.
+	pub fn check_tile_indices(&self) -> Result<(), WrlError> {
+		for (cell, &tile_index) in self.bigmap.iter().enumerate() {
+			if tile_index >= self.tile_count {
+				return Err(WrlError::InvalidTileIndex {
+					cell,
+					tile_index,
+					tile_count: self.tile_count,
+				});
+			}
+		}
+
+		Ok(())
+	}
+}
+
 #[derive(Debug, Deserialize, Serialize)]
 pub struct WRLHeader {
+	/// WRL format version from the file header.
+	pub format_version: u16,
 	pub width: u16,
 	pub height: u16,
 	pub tile_count: u16,
@@ -22,8 +119,44 @@ pub struct WRLHeader {
 	pub palette: Vec<u8>,
 }
 
-#[derive(Debug, Deserialize, Serialize)]
+/// Current version of the tail header schema, always used when writing.
+pub const WRL_TAIL_HEADER_VERSION: u16 = 2;
+
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
 pub struct WRLTailHeader {
+	pub _v: u16,
+	pub hash_id: String,
+	pub name: String,
+	pub version: String,
+	pub date: String,
+	#[serde(default)]
+	pub authors: Vec<String>,
+	pub description: String,
+	pub comment: String,
+	#[serde(default)]
+	pub tags: Vec<String>,
+	/// Recommended number of players.
+	#[serde(default)]
+	pub player_count: Option<u8>,
+	#[serde(default)]
+	pub changelog: Vec<WRLChangelogEntry>,
+	#[serde(default)]
+	pub homepage: String,
+	/// File name the map was published under, before it was installed into a slot.
+	#[serde(default)]
+	pub original_file_name: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct WRLChangelogEntry {
+	pub version: String,
+	pub date: String,
+	pub changes: String,
+}
+
+/// Tail header schema `_v: 1`, only read to migrate it to the current version.
+#[derive(Debug, Deserialize)]
+pub struct WRLTailHeaderV1 {
 	pub _v: u16,
 	pub hash_id: String,
 	pub name: String,
@@ -34,26 +167,76 @@ pub struct WRLTailHeader {
 	pub comment: String,
 }
 
+impl From<WRLTailHeaderV1> for WRLTailHeader {
+	fn from(v1: WRLTailHeaderV1) -> Self {
+		WRLTailHeader {
+			authors: if v1.author.is_empty() { Vec::new() } else { vec![v1.author] },
+			name: v1.name,
+			version: v1.version,
+			date: v1.date,
+			description: v1.description,
+			comment: v1.comment,
+			..WRLTailHeader::new(v1.hash_id)
+		}
+	}
+}
+
 impl WRLTailHeader {
 	pub fn new(hash_id: String) -> Self {
 		WRLTailHeader {
-			_v: 1,
+			_v: WRL_TAIL_HEADER_VERSION,
 			hash_id,
 			name: String::from(""),
 			version: String::from(""),
 			date: String::from(""),
-			author: String::from(""),
+			authors: Vec::new(),
 			description: String::from(""),
 			comment: String::from(""),
+			tags: Vec::new(),
+			player_count: None,
+			changelog: Vec::new(),
+			homepage: String::from(""),
+			original_file_name: String::from(""),
 		}
 	}
+
+	/// Parses the tail header JSON of any known schema version,
+	/// upgrading older versions to the current one.
+	pub fn from_json(json: &str) -> Result<WRLTailHeader, WrlError> {
+		let value: serde_json::Value = serde_json::from_str(json)
+			.map_err(|e| WrlError::InvalidTail(e.to_string()))?;
+
+		match value.get("_v").and_then(|v| v.as_u64()) {
+			Some(1) => serde_json::from_value::<WRLTailHeaderV1>(value)
+				.map(WRLTailHeader::from)
+				.map_err(|e| WrlError::InvalidTail(e.to_string())),
+			Some(2) => serde_json::from_value::<WRLTailHeader>(value)
+				.map_err(|e| WrlError::InvalidTail(e.to_string())),
+			Some(v) => Err(WrlError::InvalidTail(format!("Unsupported tail header version: {}", v))),
+			None => Err(WrlError::InvalidTail("Missing tail header version".to_string())),
+		}
+	}
+
+	/// Serializes the tail header with the current schema version.
+	pub fn to_json(&self) -> Result<String, WrlError> {
+		let tail_header = WRLTailHeader {
+			_v: WRL_TAIL_HEADER_VERSION,
+			..self.clone()
+		};
+
+		serde_json::to_string(&tail_header).map_err(|e| WrlError::Serialization(e.to_string()))
+	}
 }
 
 #[derive(Debug, Deserialize, Serialize)]
 pub struct MapMetadata {
 	pub file_path: String,
+	/// WRL format version from the file header.
+	pub format_version: u16,
 	pub width: u16,
 	pub height: u16,
 	pub minimap: Vec<u8>,
 	pub tail: WRLTailHeader,
+	/// The tail header `hash_id` does not match the map content, see `fix_tail_header_hash`.
+	pub tail_hash_mismatch: bool,
 }
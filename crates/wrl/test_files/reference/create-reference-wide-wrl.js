@@ -0,0 +1,44 @@
+#! /usr/bin/env node
+import { writeFileSync } from 'fs';
+
+// Non-square reference map: 8 vertical bands of tiles 0..7 with a top row of tile 7.
+const TILE_DATA_SIZE = 4096;
+const TILE_COUNT = 8;
+const WIDTH = 24;
+const HEIGHT = 12;
+const BAND_WIDTH = WIDTH / TILE_COUNT;
+const TILE_COLOR_STEP = 32;
+
+const cellTile = (i) => (i < WIDTH ? TILE_COUNT - 1 : ((i % WIDTH) / BAND_WIDTH) | 0);
+
+const header = Buffer.from([ 0x57, 0x52, 0x4c, 0x01, 0x00 ]);
+const width = Buffer.from([ WIDTH, 0x00 ]);
+const height = Buffer.from([ HEIGHT, 0x00 ]);
+const minimap = Buffer.from(
+	new Array(WIDTH * HEIGHT).fill(0x00).map((_, i) => cellTile(i) * TILE_COLOR_STEP),
+);
+const bigmap = Buffer.from(
+	new Array(WIDTH * HEIGHT * 2).fill(0x00).map((_, i) => i % 2 ? 0x00 : cellTile(i / 2)),
+);
+const tileCount = Buffer.from([ TILE_COUNT & 0xff, (TILE_COUNT >> 8) & 0xff ]);
+const tileData = Buffer.from(
+	new Array(TILE_COUNT * TILE_DATA_SIZE).fill(0x00).map((_, i) => ((i / TILE_DATA_SIZE) | 0) * TILE_COLOR_STEP)
+);
+const palette = Buffer.from(
+	new Array(256 * 3).fill(0x00).map((_, i) => (i / 3) | 0),
+);
+const passTable = Buffer.from(new Array(TILE_COUNT).fill(0x00));
+
+const wrlBuf = Buffer.concat([
+	header,
+	width,
+	height,
+	minimap,
+	bigmap,
+	tileCount,
+	tileData,
+	palette,
+	passTable,
+]);
+
+writeFileSync('REF_WIDE.WRL', wrlBuf);
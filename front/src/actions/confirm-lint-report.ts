@@ -0,0 +1,44 @@
+import { ErrorModal } from '^ds/error-modal/error-modal';
+
+
+/**
+ * Shows the lint findings of a map before it is installed.
+ * Maps with error findings cannot be installed.
+ * @returns {Promise<boolean>} Resolves to `true` if the user chose to install the map anyway.
+ */
+export function confirmLintReport(report: LintReport): Promise<boolean> {
+	if (report.findings.length === 0) {
+		return Promise.resolve(true);
+	}
+
+	const hasErrors = report.findings.some((finding) => finding.severity === 'error');
+	const message = report.findings
+		.map((finding) => `[${finding.severity.toUpperCase()}] ${finding.message}`)
+		.join('\n');
+
+	return new Promise((resolve) => {
+		const modal = ErrorModal({
+			title: hasErrors ? 'Map Cannot Be Installed' : 'Map Lint Report',
+			message,
+			buttons: {
+				custom: hasErrors ? undefined : {
+					title: 'INSTALL',
+					action: () => {
+						resolve(true);
+						modal.x.close();
+						modal.destroy();
+					},
+				},
+				done: true,
+			},
+		});
+
+		const _close = modal.x.close;
+		modal.x.close = function () {
+			resolve(false);
+			_close();
+		};
+
+		modal.x.open();
+	});
+}
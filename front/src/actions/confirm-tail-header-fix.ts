@@ -0,0 +1,65 @@
+import { ErrorModal } from '^ds/error-modal/error-modal';
+
+import { api } from '../api';
+
+
+/**
+ * `valid` when the tail header can be installed as is, `fix` when the installed copy has to be fixed,
+ * `cancel` when the check failed or the user declined the fix.
+ */
+export type TailHeaderFixDecision = 'valid' | 'fix' | 'cancel';
+
+/**
+ * Checks the tail header of a map before it is installed.
+ * A stale `hash_id` has to be fixed before the map can be installed.
+ * Only the installed copy is fixed, the selected file is never modified.
+ * @returns {Promise<TailHeaderFixDecision>} Resolves to the decision for the installed copy.
+ */
+export async function confirmTailHeaderFix(filePath: string): Promise<TailHeaderFixDecision> {
+	const verifyResult = await api.verifyTailHeader(filePath);
+	if (!verifyResult.ok) {
+		console.error('Failed to verify tail header:', verifyResult.error);
+		ErrorModal({
+			title: 'Import Error',
+			message: `Failed to check map: ${verifyResult.error}`,
+		}).x.open();
+		return 'cancel';
+	}
+
+	if (verifyResult.data.status === 'valid') {
+		return 'valid';
+	}
+
+	const message = [
+		'The map was edited without updating its tail header.',
+		`Stored hash: ${verifyResult.data.found}`,
+		`Actual hash: ${verifyResult.data.expected}`,
+		'Fix the tail header of the installed copy to install the map, the selected file is not changed.',
+	].join('\n');
+
+	return new Promise((resolve) => {
+		const modal = ErrorModal({
+			title: 'Stale Map Tail Header',
+			message,
+			buttons: {
+				custom: {
+					title: 'FIX',
+					action: async () => {
+						resolve('fix');
+						modal.x.close();
+						modal.destroy();
+					},
+				},
+				done: true,
+			},
+		});
+
+		const _close = modal.x.close;
+		modal.x.close = function () {
+			resolve('cancel');
+			_close();
+		};
+
+		modal.x.open();
+	});
+}
@@ -0,0 +1,17 @@
+import { api } from '^api/index.ts';
+
+
+/**
+ * Rewrites the stale tail header `hash_id` of a map file.
+ * @returns {Promise<boolean>} Resolves to `true` if the tail header was updated.
+ */
+export async function fixMapTailHeader(filePath: string): Promise<boolean> {
+	const result = await api.fixTailHeaderHash(filePath);
+
+	if (result.ok) {
+		return result.data;
+	}
+
+	console.error(result.error);
+	return false;
+}
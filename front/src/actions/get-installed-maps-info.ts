@@ -15,15 +15,17 @@ export async function getInstalledMapsInfo(): Promise<MapInfo[]> {
 						filePath: mapMetadata.file_path,
 						planetName: planetName.toUpperCase(),
 						planetSlot,
+						formatVersion: mapMetadata.format_version,
 						width: mapMetadata.width,
 						height: mapMetadata.height,
 						minimap: mapMetadata.minimap,
 						name: mapMetadata.tail.name,
 						description: mapMetadata.tail.description,
-						author: mapMetadata.tail.author,
+						author: mapMetadata.tail.authors.join(', '),
 						version: mapMetadata.tail.version,
 						date: mapMetadata.tail.date,
 						comments: mapMetadata.tail.comments,
+						tailHashMismatch: mapMetadata.tail_hash_mismatch,
 						isInstalled: true,
 					} satisfies MapInfo;
 				}).filter(function (mapInfo){
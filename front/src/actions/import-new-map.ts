@@ -4,6 +4,8 @@ import { ErrorModal } from '^ds/error-modal/error-modal';
 
 import { openFileSelectDialog } from './open-file-select-dialog';
 import { api } from '../api';
+import { confirmLintReport } from './confirm-lint-report';
+import { confirmTailHeaderFix } from './confirm-tail-header-fix';
 import { putMapInArchive } from './put-map-in-archive';
 
 
@@ -35,6 +37,25 @@ export async function importNewMap(selectedSlotName: string): Promise<boolean> {
 	}
 
 	if (fileHandles.length === 1) {
+		const lintResult = await api.lintWrlFile(fileHandles[0]);
+		if (!lintResult.ok) {
+			console.error('Failed to lint map:', lintResult.error);
+			ErrorModal({
+				title: 'Import Error',
+				message: `Failed to check map: ${lintResult.error}`,
+			}).x.open();
+			return false;
+		}
+
+		if (!(await confirmLintReport(lintResult.data))) {
+			return false;
+		}
+
+		const tailHeaderFixDecision = await confirmTailHeaderFix(fileHandles[0]);
+		if (tailHeaderFixDecision === 'cancel') {
+			return false;
+		}
+
 		const selectedSlotMapHashId = MainViewState.selectedMapHashId.value;
 		if (selectedSlotMapHashId !== null) {
 			if ((await putMapInArchive(selectedSlotMapHashId))) {
@@ -49,7 +70,7 @@ export async function importNewMap(selectedSlotName: string): Promise<boolean> {
 			}
 		}
 
-		const result = await api.installImportedMap(fileHandles[0], selectedSlotName);
+		const result = await api.installImportedMap(fileHandles[0], selectedSlotName, tailHeaderFixDecision === 'fix');
 		if (!result.ok) {
 			console.error('Failed to import map:', result.error);
 			ErrorModal({
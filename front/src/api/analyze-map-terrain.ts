@@ -0,0 +1,11 @@
+import { invoke } from '@tauri-apps/api/core';
+
+
+export async function analyzeMapTerrain(mapHashId: string): Promise<Result<TerrainAnalysis, string>> {
+	try {
+		const result = await invoke<TerrainAnalysis>('analyze_map_terrain_command', { mapHashId });
+		return { ok: true, data: result };
+	} catch (error) {
+		return { ok: false, error: (error as Error).message ?? error };
+	}
+}
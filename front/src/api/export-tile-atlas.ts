@@ -0,0 +1,11 @@
+import { invoke } from '@tauri-apps/api/core';
+
+
+export async function exportTileAtlas(mapHashId: string, outputFilePath: string): Promise<Result<TileAtlasMetadata, string>> {
+	try {
+		const result = await invoke<TileAtlasMetadata>('export_tile_atlas_command', { mapHashId, outputFilePath });
+		return { ok: true, data: result };
+	} catch (error) {
+		return { ok: false, error: (error as Error).message ?? error };
+	}
+}
@@ -0,0 +1,11 @@
+import { invoke } from '@tauri-apps/api/core';
+
+
+export async function findSimilarMaps(threshold?: number): Promise<Result<MapCluster[], string>> {
+	try {
+		const result = await invoke<MapCluster[]>('find_similar_maps_command', { threshold: threshold ?? null });
+		return { ok: true, data: result };
+	} catch (error) {
+		return { ok: false, error: (error as Error).message ?? error };
+	}
+}
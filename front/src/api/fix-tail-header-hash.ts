@@ -0,0 +1,11 @@
+import { invoke } from '@tauri-apps/api/core';
+
+
+export async function fixTailHeaderHash(filePath: string): Promise<Result<boolean, string>> {
+	try {
+		const result = await invoke<boolean>('fix_tail_header_hash_command', { filePath });
+		return { ok: true, data: result };
+	} catch (error) {
+		return { ok: false, error: (error as Error).message ?? error };
+	}
+}
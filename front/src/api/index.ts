@@ -1,8 +1,13 @@
+import { analyzeMapTerrain } from './analyze-map-terrain';
 import { archiveMapAndSaves } from './archive-map-and-saves';
+import { exportTileAtlas } from './export-tile-atlas';
+import { findSimilarMaps } from './find-similar-maps';
+import { fixTailHeaderHash } from './fix-tail-header-hash';
 import { generateBigMapPreview } from './generate-bigmap-preview';
 import { getArchivedMapsAndSaves } from './get-archived-maps-and-saves';
 import { installImportedMap } from './install-imported-map';
 import { isSetupRequired } from './is-setup-required';
+import { lintWrlFile } from './lint-wrl-file';
 import { openDirPathInFileExplorer } from './open-dir-path-in-file-explorer';
 import { readArchivedMapsMetadata } from './read-archived-maps-metadata';
 import { readInstalledMapsMetadata } from './read-installed-maps-metadata';
@@ -12,15 +17,21 @@ import { restoreMapAndSaves } from './restore-map-and-saves-command';
 import { getInstalledMapsAndSaves } from './get-installed-maps-and-saves';
 import { verifyDirPath } from './verify-dir-path';
 import { verifyGamePath } from './verify-max-path';
+import { verifyTailHeader } from './verify-tail-header';
 import { setAppPaths } from './set-app-paths';
 
 
 export const api = {
+	analyzeMapTerrain,
 	archiveMapAndSaves,
+	exportTileAtlas,
+	findSimilarMaps,
+	fixTailHeaderHash,
 	generateBigMapPreview,
 	getArchivedMapsAndSaves,
 	installImportedMap,
 	isSetupRequired,
+	lintWrlFile,
 	openDirPathInFileExplorer,
 	readArchivedMapsMetadata,
 	readInstalledMapsMetadata,
@@ -31,4 +42,5 @@ export const api = {
 	scanMapsAndSaves: getInstalledMapsAndSaves,
 	verifyDirPath,
 	verifyGamePath,
+	verifyTailHeader,
 };
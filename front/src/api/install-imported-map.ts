@@ -1,9 +1,17 @@
 import { invoke } from '@tauri-apps/api/core';
 
 
-export async function installImportedMap(importPath: string, slotName: string): Promise<Result<boolean, string>> {
+/**
+ * Installs the imported map into the slot.
+ * With `fixTailHeader` set, a stale tail header is fixed in the installed copy, the imported file is left untouched.
+ */
+export async function installImportedMap(
+	importPath: string,
+	slotName: string,
+	fixTailHeader: boolean,
+): Promise<Result<boolean, string>> {
 	try {
-		const result = await invoke<boolean>('install_imported_map_command', { importPath, slotName });
+		const result = await invoke<boolean>('install_imported_map_command', { importPath, slotName, fixTailHeader });
 		return { ok: true, data: result };
 	} catch (error) {
 		return { ok: false, error: (error as Error).message ?? error };
@@ -0,0 +1,11 @@
+import { invoke } from '@tauri-apps/api/core';
+
+
+export async function lintWrlFile(filePath: string): Promise<Result<LintReport, string>> {
+	try {
+		const result = await invoke<LintReport>('lint_wrl_file_command', { filePath });
+		return { ok: true, data: result };
+	} catch (error) {
+		return { ok: false, error: (error as Error).message ?? error };
+	}
+}
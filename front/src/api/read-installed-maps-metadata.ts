@@ -1,23 +1,36 @@
 import { invoke } from '@tauri-apps/api/core';
 
 
+interface WRLChangelogEntry {
+	version: string;
+	date: string;
+	changes: string;
+}
+
 interface WRLTailHeader {
 	_v: number;
 	hash_id: string;
 	name: string;
 	version: string;
 	date: string;
-	author: string;
+	authors: string[];
 	description: string;
 	comments: string;
+	tags: string[];
+	player_count: number | null;
+	changelog: WRLChangelogEntry[];
+	homepage: string;
+	original_file_name: string;
 }
 
 interface MapMetadata {
 	file_path: string;
+	format_version: number;
 	width: number;
 	height: number;
 	minimap: Vec<u8>;
 	tail: WRLTailHeader;
+	tail_hash_mismatch: boolean;
 }
 
 
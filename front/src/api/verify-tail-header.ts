@@ -0,0 +1,11 @@
+import { invoke } from '@tauri-apps/api/core';
+
+
+export async function verifyTailHeader(filePath: string): Promise<Result<TailHeaderVerification, string>> {
+	try {
+		const result = await invoke<TailHeaderVerification>('verify_tail_header_command', { filePath });
+		return { ok: true, data: result };
+	} catch (error) {
+		return { ok: false, error: (error as Error).message ?? error };
+	}
+}
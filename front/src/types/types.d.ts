@@ -13,6 +13,7 @@ declare interface MapInfo {
 	planetName: string;
 	planetSlot: string;
 	name: string;
+	formatVersion: number;
 	width: number;
 	height: number;
 	minimap: Vec<u8>;
@@ -21,6 +22,7 @@ declare interface MapInfo {
 	date: string;
 	version: string;
 	comments: string;
+	tailHashMismatch: boolean;
 	isInstalled: boolean;
 }
 
@@ -30,6 +32,61 @@ declare interface MapAndSaves {
 	saves: string[];
 }
 
+declare interface TileAtlasEntry {
+	tile_index: number;
+	pass_value: number;
+	usage_count: number;
+	x: number;
+	y: number;
+}
+
+declare interface TileAtlasMetadata {
+	tile_size: number;
+	columns: number;
+	rows: number;
+	tiles: TileAtlasEntry[];
+}
+
+declare type LintSeverity = 'info' | 'warning' | 'error';
+
+declare interface LintFinding {
+	severity: LintSeverity;
+	code: string;
+	message: string;
+}
+
+declare interface LintReport {
+	findings: LintFinding[];
+}
+
+declare type TailHeaderVerification =
+	| { status: 'valid' }
+	| { status: 'hash_mismatch'; expected: string; found: string };
+
+declare type MapKind = 'continental' | 'islands' | 'water';
+
+declare interface TerrainAnalysis {
+	composition: {
+		land: number;
+		water: number;
+		coast: number;
+		blocked: number;
+	};
+	landmasses: {
+		cells: number;
+		choke_point_width: number | null;
+	}[];
+	sea_regions: number[];
+	island_count: number;
+	largest_landmass: number;
+	map_kind: MapKind;
+}
+
+declare interface MapCluster {
+	map_ids: string[];
+	similarity: number;
+}
+
 declare type Result<T, E> = {
 	ok: true;
 	data: T;
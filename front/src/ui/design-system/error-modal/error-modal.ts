@@ -37,7 +37,7 @@ export function ErrorModal(options: ErrorModalOptions) {
 			Inset(2),
 			Inset(8).nodes([
 				Screen(ErrorViewState).nodes([
-					Div().class('p-16').text(options.message),
+					Div().classes('p-16', styles.message).text(options.message),
 				]),
 			]),
 			Inset(2),
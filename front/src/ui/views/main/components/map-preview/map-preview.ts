@@ -1,3 +1,4 @@
+import { fixMapTailHeader } from '^actions/fix-map-tail-header';
 import { putMapInArchive } from '^actions/put-map-in-archive';
 
 import { ArchiveViewState } from '^state/archive-view-state';
@@ -19,7 +20,7 @@ import styles from './map-preview.module.css';
 
 
 export function MapPreview() {
-	let archiveButton, replaceButton, previewSection, noPreviewSection, slotName,
+	let archiveButton, replaceButton, fixHashButton, previewSection, noPreviewSection, slotName,
 		bigmapPreviewText, bigmapPreview,
 		sizeLabel, nameLabel, versionLabel, authorLabel, dateLabel, descriptionLabel;
 
@@ -28,6 +29,7 @@ export function MapPreview() {
 			Div().classes('flex flex-spread flex-row no-grow ph-16 pv-8').nodes([
 				Heading3('Map preview').class(styles.title),
 				Div().class('flex gap-16').nodes([
+					fixHashButton = StandardButton('FIX HASH'),
 					archiveButton = StandardButton('ARCHIVE'),
 					StandardBrokenButton('EDIT DATA'),
 					replaceButton = StandardButton('REPLACE'),
@@ -101,6 +103,16 @@ export function MapPreview() {
 		ArchiveViewState.selectedMapHashId.set(selectedMapHashId, true);
 	});
 
+	fixHashButton.addEventListener('click', async function fixStaleTailHeader() {
+		const selectedMapHashId = MainViewState.selectedMapHashId.value;
+		const mapInfo = selectedMapHashId ? MainViewState.mapsInfo.value.get(selectedMapHashId) : undefined;
+		if (!mapInfo) return;
+
+		if (await fixMapTailHeader(mapInfo.filePath)) {
+			await AppState.update();
+		}
+	});
+
 	new Effect(async function updateMapPreview() {
 		const selectedMapHashId = MainViewState.selectedMapHashId.value;
 
@@ -113,6 +125,7 @@ export function MapPreview() {
 
 		previewSection.element.style.display = 'block';
 		noPreviewSection.element.style.display = 'none';
+		fixHashButton.element.style.display = 'none';
 		bigmapPreviewText.element.style.display = 'flex';
 		bigmapPreview.element.style.opacity = '0';
 
@@ -125,6 +138,7 @@ export function MapPreview() {
 				authorLabel.text(mapInfo.author);
 				dateLabel.text(mapInfo.date);
 				descriptionLabel.text(mapInfo.description.replaceAll('\\n', '\n'));
+				fixHashButton.element.style.display = mapInfo.tailHashMismatch ? '' : 'none';
 			}
 		}
 